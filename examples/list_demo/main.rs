@@ -1,7 +1,8 @@
 use std::{error::Error, io};
 
-use crossterm::{
-    event::{self, Event, KeyCode},
+// The crossterm used by ratatui, which the input adapter converts events from
+use crossterm_026::{
+    event::{self, DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -15,7 +16,10 @@ use ratatui::{
     Frame, Terminal,
 };
 
-use extra_widgets::styled_list::{ItemDisplay, ListItem, ListState, StyledList};
+use extra_widgets::styled_list::{
+    input::{InputEvent, Key, Keymap},
+    ItemDisplay, ListItem, ListState, StyledList,
+};
 
 mod demos;
 
@@ -61,17 +65,10 @@ impl AppState {
         self.focus.toggle();
     }
 
-    fn move_up(&mut self) {
-        match self.focus {
-            Focus::Picker => self.picker.prev(),
-            Focus::Example => self.examples.cycle_prev(),
-        }
-    }
-
-    fn move_down(&mut self) {
+    fn focused(&mut self) -> &mut ListState {
         match self.focus {
-            Focus::Picker => self.picker.next(),
-            Focus::Example => self.examples.cycle_next(),
+            Focus::Picker => &mut self.picker,
+            Focus::Example => &mut self.examples,
         }
     }
 }
@@ -79,37 +76,35 @@ impl AppState {
 fn main() -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let mut state = AppState::new(1, words().len());
+    let keymap = Keymap::default();
 
     loop {
         let mstate = &mut state;
         let _ = terminal.draw(|f| draw(mstate, f));
 
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char(c) if c == 'j' => {
-                    state.move_down();
-                }
-                KeyCode::Char(c) if c == 'k' => {
-                    state.move_up();
-                }
-                KeyCode::Char(c) if c == 'h' || c == 'l' => {
-                    state.switch_focus();
-                }
-                KeyCode::Char(_) => {
-                    break;
-                }
-                _ => {}
-            };
+        let Some(event) = InputEvent::from_crossterm(&event::read()?) else {
+            continue;
+        };
+        match event {
+            InputEvent::Key(key) if key.key == Key::Char('q') || key.key == Key::Esc => break,
+            InputEvent::Key(key) if key.key == Key::Tab => state.switch_focus(),
+            event => {
+                state.focused().handle_event(&event, &keymap);
+            }
         }
     }
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
     Ok(())
 }
@@ -138,14 +133,15 @@ fn draw<B: Backend>(state: &mut AppState, f: &mut Frame<B>) {
     // draw top bar
     let top_text = Spans::from(vec![
         Span::styled("Controls:", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" 'h' - "),
-        Span::styled("left,", Style::default().add_modifier(Modifier::ITALIC)),
-        Span::raw(" 'j' - "),
-        Span::styled("down,", Style::default().add_modifier(Modifier::ITALIC)),
-        Span::raw(" 'k' - "),
-        Span::styled("up,", Style::default().add_modifier(Modifier::ITALIC)),
-        Span::raw(" 'l' - "),
-        Span::styled("right", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" arrows, mouse - "),
+        Span::styled("move,", Style::default().add_modifier(Modifier::ITALIC)),
+        Span::raw(" tab - "),
+        Span::styled(
+            "switch list,",
+            Style::default().add_modifier(Modifier::ITALIC),
+        ),
+        Span::raw(" 'q' - "),
+        Span::styled("quit", Style::default().add_modifier(Modifier::ITALIC)),
     ]);
     let top_text = Paragraph::new(top_text).alignment(Alignment::Center);
     f.render_widget(top_text, bar_area);
@@ -192,19 +188,19 @@ fn draw<B: Backend>(state: &mut AppState, f: &mut Frame<B>) {
     });
 
     let code = match state.picker.selected() {
-        0 => {
+        Some(0) => {
             demos::basic(demo_list_area, state, f);
             include_str!("demos/basic.rs")
         }
-        1 => {
+        Some(1) => {
             demos::separated(demo_list_area, state, f);
             include_str!("demos/separated.rs")
         }
-        2 => {
+        Some(2) => {
             demos::fixed(demo_list_area, state, f);
             include_str!("demos/fixed.rs")
         }
-        3 => {
+        Some(3) => {
             demos::styled_items(demo_list_area, state, f);
             include_str!("demos/styled_items.rs")
         }
@@ -87,7 +87,7 @@ impl<'a, S: DateStyler> Calendar<'a, S> {
     }
 
     /// All logic to style a date goes here.
-    fn format_date(&self, date: Date) -> Span {
+    fn format_date(&self, date: Date) -> Span<'_> {
         let style = self.default_style.patch(self.events.get_style(date));
 
         if date.month() != self.display_date.month() {
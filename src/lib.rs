@@ -23,6 +23,9 @@
 //!
 //! Macros (e.g. `bold!(...)`) are gated by the `text_macros` feature.
 //!
+//! Input handling for lists ([styled_list::input]) is gated by the `input` feature. The
+//! `crossterm` and `termion` features add it along with conversions from that backend's events.
+//!
 //! ### Serde support
 //!
 //! State structs can be serialized with Serde by enabling the `serde` feature. This can be useful
@@ -30,6 +33,15 @@
 //!
 //! Serializeable states:
 //!   * [styled_list::ListState]
+//!   * [tree::TreeState]
+//!
+//! The keymaps of [styled_list::input] can be loaded with Serde too, e.g. from a config file.
+//!
+//! ### Upgrading from 0.1
+//!
+//! [styled_list::ItemDisplay], [styled_list::Indicator] and [styled_list::LineIndicators] are no
+//! longer `Copy`, since they can hold a shared separator style, a styled indicator or a closure.
+//! Clone them instead.
 //!
 #[cfg(feature = "calendar")]
 pub mod calendar;
@@ -37,5 +49,8 @@ pub mod calendar;
 #[cfg(feature = "styled_list")]
 pub mod styled_list;
 
+#[cfg(feature = "tree")]
+pub mod tree;
+
 #[cfg(feature = "text_macros")]
 pub mod text_macros;
@@ -0,0 +1,264 @@
+use ratatui::{
+    style::Style,
+    text::{Spans, Text},
+};
+
+use super::{spans, ListItem};
+
+/// What happens to [`ListItem`](super::ListItem)s that don't match the filter query.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Items that don't match are not displayed.
+    Hide,
+    /// Items that don't match are displayed with the dim style patched in.
+    Dim,
+}
+
+/// Filtering for a [`StyledList`](super::StyledList).
+///
+/// The query is stored in the [`ListState`](super::ListState) (see
+/// [`ListState::set_filter`](super::ListState::set_filter)), and each item's text is fuzzy matched
+/// against it when the list is rendered. Characters of the query must appear in the item in order,
+/// but not necessarily next to each other. Matching ignores case unless the query contains an
+/// uppercase character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub(super) mode: FilterMode,
+    pub(super) highlight_style: Style,
+    pub(super) dim_style: Style,
+}
+
+impl Filter {
+    pub fn new(mode: FilterMode) -> Self {
+        Self {
+            mode,
+            highlight_style: Style::default(),
+            dim_style: Style::default(),
+        }
+    }
+
+    /// The style patched into the characters of an item that matched the query.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// The style patched into items that don't match when using [`FilterMode::Dim`].
+    pub fn dim_style(mut self, style: Style) -> Self {
+        self.dim_style = style;
+        self
+    }
+
+    /// Match every item (paired with its index) against `query`, highlighting the matched
+    /// characters. Returns the items to display (with their index and whether they matched), the
+    /// indices of all the matching items, and the index of the best match.
+    #[allow(clippy::type_complexity)]
+    pub(super) fn apply<'a, I>(
+        &self,
+        items: I,
+        query: &str,
+    ) -> (Vec<(usize, ListItem<'a>, bool)>, Vec<usize>, Option<usize>)
+    where
+        I: IntoIterator<Item = (usize, ListItem<'a>)>,
+    {
+        let mut matches = Vec::new();
+        let mut best: Option<(i64, usize)> = None;
+        let mut res = Vec::new();
+        for (i, mut it) in items {
+            // Headers aren't filtered, so the sections stay labeled
+            if it.header {
+                res.push((i, it, true));
+                continue;
+            }
+            match fuzzy_match(query, &plain_text(&it.content)) {
+                Some(m) => {
+                    if best.is_none_or(|(score, _)| m.score > score) {
+                        best = Some((m.score, i));
+                    }
+                    matches.push(i);
+                    it.content = highlight(it.content, &m.positions, self.highlight_style);
+                    res.push((i, it, true));
+                }
+                None if self.mode == FilterMode::Dim => res.push((i, it, false)),
+                None => {}
+            }
+        }
+        (res, matches, best.map(|(_, i)| i))
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new(FilterMode::Hide)
+    }
+}
+
+/// The result of matching a query against some text. `positions` are the char indices of the
+/// matched characters of the text, in ascending order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct FuzzyMatch {
+    pub(super) score: i64,
+    pub(super) positions: Vec<usize>,
+}
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 8;
+const BONUS_WORD_START: i64 = 8;
+const PENALTY_GAP: i64 = 1;
+
+/// Match `query` against `text`, returning `None` if the query isn't a subsequence of the text.
+///
+/// The leftmost occurrence of the query is found, and then tightened by searching backwards from
+/// its end, so the match covers as few characters as possible. The score rewards matches that
+/// are consecutive or start words, and penalizes gaps.
+pub(super) fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let fold = |c: char| {
+        if ignore_case {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    };
+    let query: Vec<char> = query.chars().map(fold).collect();
+    let text: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    // forward pass: find where the leftmost match ends
+    let mut qi = 0;
+    let mut end = None;
+    for (i, &c) in text.iter().enumerate() {
+        if fold(c) == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // backward pass: find the closest start for that end
+    let mut positions = vec![0; query.len()];
+    let mut qi = query.len();
+    for i in (0..=end).rev() {
+        if fold(text[i]) == query[qi - 1] {
+            qi -= 1;
+            positions[qi] = i;
+            if qi == 0 {
+                break;
+            }
+        }
+    }
+
+    let is_word_start = |i: usize| {
+        i == 0
+            || !text[i - 1].is_alphanumeric()
+            || (text[i - 1].is_lowercase() && text[i].is_uppercase())
+    };
+    let mut score = 0;
+    for (n, &p) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        if is_word_start(p) {
+            score += BONUS_WORD_START;
+        }
+        if n > 0 {
+            let gap = p - positions[n - 1] - 1;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * gap as i64;
+            }
+        }
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// The text of an item as a string, with lines separated by `'\n'`.
+pub(super) fn plain_text(text: &Text) -> String {
+    text.lines
+        .iter()
+        .map(|l| l.0.iter().map(|s| s.content.as_ref()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Patch `style` into the characters of `text` at `positions`, which are char indices into the
+/// [`plain_text`] of `text`.
+pub(super) fn highlight<'a>(text: Text<'a>, positions: &[usize], style: Style) -> Text<'a> {
+    let mut offset = 0;
+    let mut positions = positions.iter().copied().peekable();
+    let lines = text
+        .lines
+        .into_iter()
+        .map(|line| {
+            let len: usize = line.0.iter().map(|s| s.content.chars().count()).sum();
+            let mut in_line = Vec::new();
+            while let Some(p) = positions.next_if(|&p| p < offset + len) {
+                in_line.push(p - offset);
+            }
+            // skip the line and the '\n'
+            offset += len + 1;
+            if in_line.is_empty() {
+                line
+            } else {
+                spans::patch_chars(line, &in_line, style)
+            }
+        })
+        .collect::<Vec<Spans>>();
+    Text { lines }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ratatui::{style::Color, text::Span};
+
+    #[test]
+    fn matches() {
+        assert_eq!(
+            fuzzy_match("abc", "xaxbxc").unwrap().positions,
+            vec![1, 3, 5]
+        );
+        assert_eq!(fuzzy_match("ABC", "abc"), None);
+        assert_eq!(fuzzy_match("abc", "ABC").unwrap().positions, vec![0, 1, 2]);
+        assert_eq!(fuzzy_match("abd", "abc"), None);
+    }
+
+    #[test]
+    fn tightens_match() {
+        // the first 'a' is skipped, since the 'a' closer to 'b' is a better match
+        assert_eq!(fuzzy_match("ab", "a_ab").unwrap().positions, vec![2, 3]);
+    }
+
+    #[test]
+    fn scores() {
+        let consecutive = fuzzy_match("foo", "xfoo").unwrap().score;
+        let spread = fuzzy_match("foo", "xfxoxo").unwrap().score;
+        let word_start = fuzzy_match("foo", "x foo").unwrap().score;
+        assert!(consecutive > spread);
+        assert!(word_start > consecutive);
+    }
+
+    #[test]
+    fn highlights_across_lines() {
+        let hl = Style::default().fg(Color::Red);
+        let text = Text::from("ab\ncd");
+        let m = fuzzy_match("bc", &plain_text(&text)).unwrap();
+        assert_eq!(m.positions, vec![1, 3]);
+        let text = highlight(text, &m.positions, hl);
+        assert_eq!(
+            text.lines,
+            vec![
+                Spans::from(vec![Span::raw("a"), Span::styled("b", hl)]),
+                Spans::from(vec![Span::styled("c", hl), Span::raw("d")]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, text::Spans};
+
+use super::{
+    layout::{GridCells, ListLayout},
+    line_iters::ToLines,
+    spans,
+    style_rule::RuleContext,
+    DisplayLine, LineKind, ListItem, ListState, StyledList,
+};
+
+/// The number of columns in an [`ItemDisplay::Grid`](super::ItemDisplay::Grid).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Columns {
+    /// This many columns, splitting the width of the list evenly.
+    Fixed(usize),
+    /// As many columns as fit the widest item, with a blank column between cells.
+    Auto,
+}
+
+/// A cell of the grid: the style of its item, and the lines to draw.
+struct Cell<'a> {
+    style: Style,
+    lines: Vec<DisplayLine<'a>>,
+}
+
+impl<'a> StyledList<'a, ()> {
+    /// Render `items` flowed into a grid. See [`ItemDisplay::Grid`](super::ItemDisplay::Grid).
+    pub(super) fn render_grid<I>(
+        mut self,
+        items: I,
+        columns: Columns,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut ListState,
+    ) where
+        I: Iterator<Item = ListItem<'a>>,
+    {
+        let (scrollbar_area, area) = self.frame(area, buf);
+
+        // Style the items, the same way as the lines of a list
+        state.set_matches(None, None);
+        let rules = RuleContext::new(state);
+        let mut keys = Vec::new();
+        let items: Vec<ListItem<'a>> = items
+            .enumerate()
+            .map(|(i, mut it)| {
+                if let Some(key) = &it.key {
+                    keys.push((i, key.clone()));
+                }
+                let base = self.default_style.patch(rules.style(&self.style_rules, i));
+                if Some(i) == state.selected || state.is_marked(i) {
+                    it = it.indicators(self.selected_indicator.clone());
+                    it.style = base.patch(it.style.patch(self.selected_style));
+                } else {
+                    it.style = base.patch(it.style);
+                }
+                it.indicators = it.indicators.at_frame(state.frame);
+                it
+            })
+            .collect();
+
+        // Lay out the cells. Each cell has its own indicator columns, as wide as the widest
+        // indicator of any item.
+        let (left_width, right_width) =
+            self.indicator_widths(items.iter().map(|it| it.indicators.widths()));
+        let indicators = left_width + right_width;
+        let (columns, stride, cell_width) = match columns {
+            Columns::Fixed(n) => {
+                let n = n.max(1);
+                let width = (area.width as usize / n) as u16;
+                (n, width, width)
+            }
+            Columns::Auto => {
+                let widest = items
+                    .iter()
+                    .flat_map(|it| it.content.lines.iter())
+                    .map(Spans::width)
+                    .max()
+                    .unwrap_or(0);
+                let width = (widest as u16).saturating_add(indicators).min(area.width);
+                let n = (area.width + 1) / (width + 1);
+                (n.max(1) as usize, width + 1, width)
+            }
+        };
+        let text_width = cell_width.saturating_sub(indicators) as usize;
+
+        let len = items.len();
+        let cells: Vec<Cell> = items
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut it)| {
+                if let Some(mode) = self.wrap {
+                    it.content = spans::wrap_text(it.content, text_width, mode);
+                }
+                Cell {
+                    style: it.style,
+                    lines: ToLines::new(it, i, Some(i) == state.selected).collect(),
+                }
+            })
+            .collect();
+        let rows: Vec<&[Cell]> = cells.chunks(columns).collect();
+        let heights: Vec<usize> = rows
+            .iter()
+            .map(|row| row.iter().map(|c| c.lines.len()).max().unwrap_or(0).max(1))
+            .collect();
+
+        // Move the window by whole rows, so the selected row is visible. If there is room, show
+        // more rows from the end of the grid.
+        let height = area.height as usize;
+        let selected_row = min_row(state.selected.unwrap_or(0) / columns, rows.len());
+        let mut top = min_row(state.window_anchor.0 / columns, rows.len()).min(selected_row);
+        while top < selected_row && heights[top..=selected_row].iter().sum::<usize>() > height {
+            top += 1;
+        }
+        while top > 0 && heights[top - 1..].iter().sum::<usize>() <= height {
+            top -= 1;
+        }
+
+        // Draw the rows that fit, recording the first cell of each row drawn.
+        let mut drawn = Vec::new();
+        for (r, row) in rows.iter().enumerate().skip(top) {
+            for line in 0..heights[r] {
+                if drawn.len() == height {
+                    break;
+                }
+                let y = area.y + drawn.len() as u16;
+                for (c, cell) in row.iter().enumerate() {
+                    let x = area.x + c as u16 * stride;
+                    let cell_area = Rect::new(x, y, cell_width.min(area.right() - x), 1);
+                    let widths = (left_width, right_width);
+                    draw_cell_line(cell, line, cell_area, widths, buf);
+                }
+                drawn.push(LineKind::Item {
+                    index: r * columns,
+                    line,
+                    last: line + 1 == heights[r],
+                });
+            }
+        }
+
+        let total = heights.iter().sum();
+        state.set_headers(Vec::new(), state.selected);
+        state.hints.clear();
+        state.set_pos(heights[..top].iter().sum());
+        state.set_window(drawn.len(), Some(total));
+        state.set_h_bounds(0, 0, text_width);
+        state.window_anchor = (top * columns, 0);
+        state.set_keys(&keys);
+        if let (Some(sb), Some(sb_area)) = (self.scrollbar, scrollbar_area) {
+            sb.render(sb_area, buf, state.window_first, drawn.len(), total);
+        }
+        state.layout = ListLayout {
+            area,
+            gutter_width: 0,
+            left_width,
+            right_width,
+            rows: drawn,
+            grid: Some(GridCells {
+                columns,
+                stride,
+                width: cell_width,
+                len,
+            }),
+        };
+    }
+}
+
+/// Draw line `line` of a cell, which is blank if the item has fewer lines than its row. `widths`
+/// are the widths of the left and right indicators.
+fn draw_cell_line(cell: &Cell, line: usize, area: Rect, widths: (u16, u16), buf: &mut Buffer) {
+    buf.set_style(area, cell.style);
+    let Some(l) = cell.lines.get(line) else {
+        return;
+    };
+
+    let left = widths.0.min(area.width);
+    let right = widths.1.min(area.width - left);
+    let width = area.width - left - right;
+    if left > 0 {
+        buf.set_spans(area.x, area.y, &l.left_indicator, left);
+    }
+    if right > 0 {
+        buf.set_spans(area.x + left + width, area.y, &l.right_indicator, right);
+    }
+    buf.set_spans(area.x + left, area.y, &l.line, width);
+}
+
+/// Limit `row` to the rows of the grid.
+fn min_row(row: usize, rows: usize) -> usize {
+    row.min(rows.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::{style::Color, widgets::StatefulWidget};
+
+    use super::super::{GridEdge, Hit, HitColumn, Indicator, ItemDisplay, LineIndicators};
+    use super::*;
+
+    fn items() -> Vec<ListItem<'static>> {
+        ["a", "bb", "c", "d", "ee", "f", "g"]
+            .into_iter()
+            .map(ListItem::new)
+            .collect()
+    }
+
+    fn render(
+        list: StyledList<'static, Vec<ListItem<'static>>>,
+        area: Rect,
+        state: &mut ListState,
+    ) -> (Buffer, Vec<String>) {
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(list, area, &mut buf, state);
+        let rows = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf.get(x, y).symbol.as_str())
+                    .collect()
+            })
+            .collect();
+        (buf, rows)
+    }
+
+    #[test]
+    fn flows_into_columns() {
+        let list = StyledList::new(items()).item_display(ItemDisplay::Grid(Columns::Auto));
+        let mut state = ListState::new(7);
+        let (_, rows) = render(list, Rect::new(0, 0, 9, 3), &mut state);
+        assert_eq!(rows, vec!["a  bb c  ", "d  ee f  ", "g        "]);
+
+        let list = StyledList::new(items()).item_display(ItemDisplay::Grid(Columns::Fixed(2)));
+        let (_, rows) = render(list, Rect::new(0, 0, 6, 2), &mut state);
+        assert_eq!(rows, vec!["a  bb ", "c  d  "]);
+    }
+
+    #[test]
+    fn selection_per_cell() {
+        let list = StyledList::new(items())
+            .item_display(ItemDisplay::Grid(Columns::Fixed(3)))
+            .selected_style(Style::default().bg(Color::Red))
+            .selected_indicator(LineIndicators::default().set_left(Indicator::Char(">")))
+            .show_left_indicator();
+        let mut state = ListState::new(7);
+        state.select(4);
+        let (buf, rows) = render(list, Rect::new(0, 0, 9, 2), &mut state);
+        assert_eq!(rows, vec![" a  bb c ", " d >ee f "]);
+        assert_eq!(buf.get(3, 1).bg, Color::Red);
+        assert_eq!(buf.get(5, 1).bg, Color::Red);
+        assert_eq!(buf.get(6, 1).bg, Color::Reset);
+        assert_eq!(
+            state.item_at(4, 1),
+            Some(Hit::Item {
+                index: 4,
+                line: 0,
+                column: HitColumn::Content
+            })
+        );
+        assert_eq!(
+            state.item_at(3, 1),
+            Some(Hit::Item {
+                index: 4,
+                line: 0,
+                column: HitColumn::LeftIndicator
+            })
+        );
+    }
+
+    #[test]
+    fn window_follows_selected_row() {
+        let list = || StyledList::new(items()).item_display(ItemDisplay::Grid(Columns::Fixed(2)));
+        let mut state = ListState::new(7);
+        state.select(6);
+        let (_, rows) = render(list(), Rect::new(0, 0, 4, 2), &mut state);
+        assert_eq!(rows, vec!["eef ", "g   "]);
+        assert_eq!(state.visible_items(), 4..7);
+        assert_eq!(state.visible_lines(), 2..4);
+
+        state.select(3);
+        let (_, rows) = render(list(), Rect::new(0, 0, 4, 2), &mut state);
+        assert_eq!(rows, vec!["c d ", "eef "]);
+        state.select(0);
+        let (_, rows) = render(list(), Rect::new(0, 0, 4, 2), &mut state);
+        assert_eq!(rows, vec!["a bb", "c d "]);
+    }
+
+    #[test]
+    fn navigation() {
+        let list = StyledList::new(items()).item_display(ItemDisplay::Grid(Columns::Fixed(3)));
+        let mut state = ListState::new(7);
+        render(list, Rect::new(0, 0, 9, 3), &mut state);
+        // a bb c
+        // d ee f
+        // g
+
+        state.select_right(GridEdge::Stop);
+        assert_eq!(state.selected(), Some(1));
+        state.select_down(GridEdge::Stop);
+        assert_eq!(state.selected(), Some(4));
+        // the last row is short
+        state.select_down(GridEdge::Stop);
+        assert_eq!(state.selected(), Some(6));
+        state.select_down(GridEdge::Stop);
+        assert_eq!(state.selected(), Some(6));
+        state.select_down(GridEdge::Wrap);
+        assert_eq!(state.selected(), Some(0));
+        state.select_up(GridEdge::Wrap);
+        assert_eq!(state.selected(), Some(6));
+        state.select_right(GridEdge::Wrap);
+        assert_eq!(state.selected(), Some(6));
+
+        state.select(2);
+        state.select_right(GridEdge::Stop);
+        assert_eq!(state.selected(), Some(2));
+        state.select_right(GridEdge::Wrap);
+        assert_eq!(state.selected(), Some(0));
+        state.select_left(GridEdge::Wrap);
+        assert_eq!(state.selected(), Some(2));
+        state.select_right(GridEdge::Continue);
+        assert_eq!(state.selected(), Some(3));
+        state.select_left(GridEdge::Continue);
+        assert_eq!(state.selected(), Some(2));
+
+        // up and down continue into the next column
+        state.select(2);
+        state.select_up(GridEdge::Continue);
+        assert_eq!(state.selected(), Some(4));
+        state.select(6);
+        state.select_down(GridEdge::Continue);
+        assert_eq!(state.selected(), Some(1));
+
+        // a page is every row shown in full
+        state.select(0);
+        state.page_down();
+        assert_eq!(state.selected(), Some(6));
+    }
+}
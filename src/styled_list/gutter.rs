@@ -0,0 +1,87 @@
+use super::ListState;
+
+const HINT_KEYS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// The numbers drawn in the gutter of a [`StyledList`](super::StyledList), to the left of the
+/// items. See [`StyledList::numbering`](super::StyledList::numbering).
+///
+/// Only the first line of an item is numbered, and section headers aren't numbered at all.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Numbering {
+    /// The position of the item in the list, starting from 1. This doesn't change when the list
+    /// is filtered.
+    Absolute,
+    /// The number of items between the item and the selection, like vim's `relativenumber`. The
+    /// selected item is 0. When the list is filtered, only the matching items are counted.
+    Relative,
+    /// A key for each item in the window, `a` to `z` from the top (or two letters if there are
+    /// more items than letters). Pass a typed key to [`ListState::select_hint`] to select the
+    /// item.
+    Hint,
+}
+
+impl Numbering {
+    /// The width of the gutter, including the space between the numbers and the items.
+    pub(super) fn width(self, state: &ListState, height: usize) -> u16 {
+        let digits = |n: usize| n.max(1).ilog10() as u16 + 1;
+        let width = match self {
+            Numbering::Absolute => digits(state.size),
+            Numbering::Relative => digits(state.filtered_len().saturating_sub(1)),
+            Numbering::Hint => hint_len(height) as u16,
+        };
+        width + 1
+    }
+
+    /// The number of the item at `index`. `hint` is the position of the item among the
+    /// numbered items in the window, and `hints` is the number of items that get a hint.
+    pub(super) fn number(
+        self,
+        state: &ListState,
+        index: usize,
+        hint: usize,
+        hints: usize,
+    ) -> String {
+        match self {
+            Numbering::Absolute => (index + 1).to_string(),
+            Numbering::Relative => {
+                let position = |i| state.matched_position(i);
+                position(index)
+                    .abs_diff(position(state.selected.unwrap_or(0)))
+                    .to_string()
+            }
+            Numbering::Hint => hint_label(hint, hints),
+        }
+    }
+}
+
+/// The number of letters in each hint, when there are `count` of them.
+fn hint_len(count: usize) -> usize {
+    if count <= HINT_KEYS.len() {
+        1
+    } else {
+        2
+    }
+}
+
+/// The hint key for the `n`th of `count` items.
+pub(super) fn hint_label(n: usize, count: usize) -> String {
+    let keys = HINT_KEYS.len();
+    let key = |i: usize| char::from(HINT_KEYS[i % keys]);
+    match hint_len(count) {
+        1 => key(n).to_string(),
+        _ => [key(n / keys), key(n)].iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn labels() {
+        assert_eq!(hint_label(0, 3), "a");
+        assert_eq!(hint_label(25, 26), "z");
+        assert_eq!(hint_label(0, 27), "aa");
+        assert_eq!(hint_label(27, 30), "bb");
+    }
+}
@@ -0,0 +1,118 @@
+use crossterm_026::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use super::{InputEvent, Key, KeyPress, Modifiers, MouseAction};
+
+impl InputEvent {
+    /// Convert a crossterm event. Returns `None` for events that aren't a key press (or repeat),
+    /// a left click or a turn of the mouse wheel, and for keys without a [`Key`].
+    pub fn from_crossterm(event: &Event) -> Option<Self> {
+        match event {
+            Event::Key(key) => key_press(key).map(InputEvent::Key),
+            Event::Mouse(mouse) => mouse_event(mouse),
+            _ => None,
+        }
+    }
+}
+
+fn modifiers(m: KeyModifiers) -> Modifiers {
+    Modifiers {
+        ctrl: m.contains(KeyModifiers::CONTROL),
+        alt: m.contains(KeyModifiers::ALT),
+        shift: m.contains(KeyModifiers::SHIFT),
+    }
+}
+
+fn key_press(event: &KeyEvent) -> Option<KeyPress> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+    let key = match event.code {
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => Key::BackTab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    let mut modifiers = modifiers(event.modifiers);
+    // The character is already shifted, and BackTab is shift-tab
+    if let Key::Char(_) | Key::BackTab = key {
+        modifiers.shift = false;
+    }
+    Some(KeyPress { key, modifiers })
+}
+
+fn mouse_event(event: &MouseEvent) -> Option<InputEvent> {
+    let action = match event.kind {
+        MouseEventKind::Down(MouseButton::Left) => MouseAction::Click,
+        MouseEventKind::ScrollUp => MouseAction::ScrollUp,
+        MouseEventKind::ScrollDown => MouseAction::ScrollDown,
+        _ => return None,
+    };
+    Some(InputEvent::Mouse {
+        action,
+        x: event.column,
+        y: event.row,
+        modifiers: modifiers(event.modifiers),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts() {
+        let key = |code, m| Event::Key(KeyEvent::new(code, m));
+        assert_eq!(
+            InputEvent::from_crossterm(&key(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            Some('G'.into())
+        );
+        assert_eq!(
+            InputEvent::from_crossterm(&key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(KeyPress::from('d').ctrl().into())
+        );
+        assert_eq!(
+            InputEvent::from_crossterm(&key(KeyCode::Down, KeyModifiers::SHIFT)),
+            Some(KeyPress::new(Key::Down).shift().into())
+        );
+        assert_eq!(
+            InputEvent::from_crossterm(&key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Key::BackTab.into())
+        );
+        assert_eq!(
+            InputEvent::from_crossterm(&key(KeyCode::CapsLock, KeyModifiers::NONE)),
+            None
+        );
+
+        let click = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row: 4,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(
+            InputEvent::from_crossterm(&click),
+            Some(InputEvent::Mouse {
+                action: MouseAction::Click,
+                x: 3,
+                y: 4,
+                modifiers: Modifiers::default(),
+            })
+        );
+    }
+}
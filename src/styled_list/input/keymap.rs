@@ -0,0 +1,222 @@
+#[cfg(feature = "serde")]
+use serde_derive::{Deserialize, Serialize};
+
+use super::{Key, KeyPress};
+
+/// What a key does to a [`ListState`](super::super::ListState). See
+/// [`ListState::handle_event`](super::super::ListState::handle_event).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Action {
+    /// Select the previous item, or the item above in a grid
+    Up,
+    /// Select the next item, or the item below in a grid
+    Down,
+    /// Select the item to the left in a grid, otherwise scroll the content left by
+    /// [`Keymap::scroll_columns`]
+    Left,
+    /// Select the item to the right in a grid, otherwise scroll the content right by
+    /// [`Keymap::scroll_columns`]
+    Right,
+    CycleNext,
+    CyclePrev,
+    First,
+    Last,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    WindowTop,
+    WindowMiddle,
+    WindowBottom,
+    /// Scroll the window a line up without moving the selection
+    ScrollUp,
+    /// Scroll the window a line down without moving the selection
+    ScrollDown,
+    /// Scroll back to the start of the lines
+    LineStart,
+    /// Scroll to the end of the widest line
+    LineEnd,
+    /// Move the line cursor up, see [`ListState::prev_line`](super::super::ListState::prev_line)
+    PrevLine,
+    /// Move the line cursor down, see
+    /// [`ListState::next_line`](super::super::ListState::next_line)
+    NextLine,
+    ToggleMark,
+    /// Extend the marks to the previous item
+    ExtendUp,
+    /// Extend the marks to the next item
+    ExtendDown,
+    MarkAll,
+    InvertMarks,
+    ClearMarks,
+}
+
+/// A key bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Binding {
+    pub key: KeyPress,
+    pub action: Action,
+}
+
+/// The actions bound to keys, for [`ListState::handle_event`](super::super::ListState::handle_event).
+///
+/// Start from one of the default keymaps and [`bind`](Keymap::bind) any other keys, or load a
+/// keymap from a config file with the `serde` feature. A keymap is serialized as its list of
+/// bindings, e.g. in TOML:
+///
+/// ```toml
+/// scroll_lines = 3
+/// scroll_columns = 4
+///
+/// [[bindings]]
+/// key = { key = { Char = "j" } }
+/// action = "Down"
+///
+/// [[bindings]]
+/// key = { key = "PageDown", modifiers = { ctrl = true } }
+/// action = "Last"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Keymap {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bindings: Vec<Binding>,
+    /// The lines scrolled by a turn of the mouse wheel
+    #[cfg_attr(feature = "serde", serde(default = "default_scroll_lines"))]
+    pub scroll_lines: usize,
+    /// The columns scrolled by [`Action::Left`] and [`Action::Right`] outside of a grid
+    #[cfg_attr(feature = "serde", serde(default = "default_scroll_columns"))]
+    pub scroll_columns: usize,
+}
+
+fn default_scroll_lines() -> usize {
+    3
+}
+
+fn default_scroll_columns() -> usize {
+    4
+}
+
+impl Keymap {
+    /// A keymap with no bindings.
+    pub fn empty() -> Self {
+        Keymap {
+            bindings: Vec::new(),
+            scroll_lines: default_scroll_lines(),
+            scroll_columns: default_scroll_columns(),
+        }
+    }
+
+    /// Bind `key` to `action`, replacing any action it was bound to.
+    pub fn bind<K: Into<KeyPress>>(mut self, key: K, action: Action) -> Self {
+        let key = key.into();
+        self.bindings.retain(|b| b.key != key);
+        self.bindings.push(Binding { key, action });
+        self
+    }
+
+    /// Remove the binding of `key`.
+    pub fn unbind<K: Into<KeyPress>>(mut self, key: K) -> Self {
+        let key = key.into();
+        self.bindings.retain(|b| b.key != key);
+        self
+    }
+
+    /// The action bound to `key`.
+    pub fn action(&self, key: KeyPress) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|b| b.key == key)
+            .map(|b| b.action)
+    }
+
+    fn bind_all<K: Into<KeyPress>>(self, bindings: impl IntoIterator<Item = (K, Action)>) -> Self {
+        bindings
+            .into_iter()
+            .fold(self, |keymap, (key, action)| keymap.bind(key, action))
+    }
+
+    /// The arrow and navigation keys, with shift extending the marks, space to toggle a mark and
+    /// ctrl-a to mark everything. This is the default keymap.
+    pub fn arrows() -> Self {
+        Keymap::empty()
+            .bind_all([
+                (Key::Up, Action::Up),
+                (Key::Down, Action::Down),
+                (Key::Left, Action::Left),
+                (Key::Right, Action::Right),
+                (Key::PageUp, Action::PageUp),
+                (Key::PageDown, Action::PageDown),
+                (Key::Home, Action::First),
+                (Key::End, Action::Last),
+            ])
+            .bind_all([
+                (KeyPress::new(Key::Up).shift(), Action::ExtendUp),
+                (KeyPress::new(Key::Down).shift(), Action::ExtendDown),
+                (KeyPress::from(' '), Action::ToggleMark),
+                (KeyPress::from('a').ctrl(), Action::MarkAll),
+            ])
+    }
+
+    /// Vim style keys: `j`/`k` to move, `H`/`M`/`L` for the window, the ctrl keys for paging and
+    /// scrolling, and `0`/`$` for the ends of the lines. Space toggles a mark, and `J`/`K` extend
+    /// the marks.
+    ///
+    /// `G` selects the last item, but a keymap only binds single keys, so vim's `gg` can't be
+    /// bound. Home selects the first item instead (and End the last).
+    pub fn vim() -> Self {
+        Keymap::empty()
+            .bind_all([
+                ('j', Action::Down),
+                ('k', Action::Up),
+                ('h', Action::Left),
+                ('l', Action::Right),
+                ('G', Action::Last),
+                ('H', Action::WindowTop),
+                ('M', Action::WindowMiddle),
+                ('L', Action::WindowBottom),
+                ('0', Action::LineStart),
+                ('$', Action::LineEnd),
+                (' ', Action::ToggleMark),
+                ('J', Action::ExtendDown),
+                ('K', Action::ExtendUp),
+            ])
+            .bind_all([(Key::Home, Action::First), (Key::End, Action::Last)])
+            .bind_all([
+                (KeyPress::from('f').ctrl(), Action::PageDown),
+                (KeyPress::from('b').ctrl(), Action::PageUp),
+                (KeyPress::from('d').ctrl(), Action::HalfPageDown),
+                (KeyPress::from('u').ctrl(), Action::HalfPageUp),
+                (KeyPress::from('e').ctrl(), Action::ScrollDown),
+                (KeyPress::from('y').ctrl(), Action::ScrollUp),
+            ])
+    }
+
+    /// Emacs style keys: ctrl-n/ctrl-p to move, ctrl-f/ctrl-b sideways, `alt-<`/`alt->` for the
+    /// ends, ctrl-v/alt-v for paging and ctrl-a/ctrl-e for the ends of the lines. Ctrl-space
+    /// toggles a mark, and ctrl-g clears the marks.
+    pub fn emacs() -> Self {
+        Keymap::empty().bind_all([
+            (KeyPress::from('n').ctrl(), Action::Down),
+            (KeyPress::from('p').ctrl(), Action::Up),
+            (KeyPress::from('f').ctrl(), Action::Right),
+            (KeyPress::from('b').ctrl(), Action::Left),
+            (KeyPress::from('<').alt(), Action::First),
+            (KeyPress::from('>').alt(), Action::Last),
+            (KeyPress::from('v').ctrl(), Action::PageDown),
+            (KeyPress::from('v').alt(), Action::PageUp),
+            (KeyPress::from('a').ctrl(), Action::LineStart),
+            (KeyPress::from('e').ctrl(), Action::LineEnd),
+            (KeyPress::from(' ').ctrl(), Action::ToggleMark),
+            (KeyPress::from('g').ctrl(), Action::ClearMarks),
+        ])
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::arrows()
+    }
+}
@@ -0,0 +1,310 @@
+//! Input handling for a [`StyledList`](super::StyledList).
+//!
+//! Terminal events are turned into the backend neutral [`InputEvent`] (with the `crossterm` or
+//! `termion` feature, see `InputEvent::from_crossterm` and `InputEvent::from_termion`), and
+//! [`ListState::handle_event`] applies them to the list through a [`Keymap`]:
+//!
+//! ```
+//! use extra_widgets::styled_list::input::{InputEvent, Key, Keymap};
+//! use extra_widgets::styled_list::ListState;
+//!
+//! let keymap = Keymap::vim();
+//! let mut state = ListState::new(10);
+//! state.handle_event(&InputEvent::from(Key::Char('j')), &keymap);
+//! assert_eq!(state.selected(), Some(1));
+//! ```
+#[cfg(feature = "crossterm")]
+mod crossterm;
+mod keymap;
+#[cfg(feature = "termion")]
+mod termion;
+
+#[cfg(feature = "serde")]
+use serde_derive::{Deserialize, Serialize};
+
+pub use keymap::{Action, Binding, Keymap};
+
+use super::{GridEdge, Hit, ListState};
+
+/// A key on the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Key {
+    /// A character key. The character is already upper case if shift was held.
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Backspace,
+    Delete,
+    Insert,
+    /// A function key, e.g. `F(1)`
+    F(u8),
+}
+
+/// The modifier keys held during an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Shift is only set for keys other than [`Key::Char`], which has the shifted character.
+    pub shift: bool,
+}
+
+/// A key with the modifiers held while it was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct KeyPress {
+    pub key: Key,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modifiers: Modifiers,
+}
+
+impl KeyPress {
+    pub fn new(key: Key) -> Self {
+        KeyPress {
+            key,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    /// The key with control held
+    pub fn ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    /// The key with alt held
+    pub fn alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    /// The key with shift held
+    pub fn shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+}
+
+impl From<Key> for KeyPress {
+    fn from(key: Key) -> Self {
+        KeyPress::new(key)
+    }
+}
+
+impl From<char> for KeyPress {
+    fn from(c: char) -> Self {
+        KeyPress::new(Key::Char(c))
+    }
+}
+
+/// What was done with the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    /// The left button was pressed
+    Click,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// An input event, independent of the terminal backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    Key(KeyPress),
+    /// A mouse event at the screen position `x`, `y`
+    Mouse {
+        action: MouseAction,
+        x: u16,
+        y: u16,
+        modifiers: Modifiers,
+    },
+}
+
+impl From<KeyPress> for InputEvent {
+    fn from(key: KeyPress) -> Self {
+        InputEvent::Key(key)
+    }
+}
+
+impl From<Key> for InputEvent {
+    fn from(key: Key) -> Self {
+        InputEvent::Key(key.into())
+    }
+}
+
+impl From<char> for InputEvent {
+    fn from(c: char) -> Self {
+        InputEvent::Key(c.into())
+    }
+}
+
+impl ListState {
+    /// Apply an input event to the list, with keys looked up in `keymap`. Returns whether the
+    /// event was used, so the application can handle any others.
+    ///
+    /// Clicking an item selects it, or extends the marks to it with shift held (see
+    /// [`ListState::extend_to`]). Clicks on section headers aren't used. The mouse wheel scrolls the window by
+    /// [`Keymap::scroll_lines`]. Mouse events use the layout of the last render.
+    pub fn handle_event(&mut self, event: &InputEvent, keymap: &Keymap) -> bool {
+        match *event {
+            InputEvent::Key(key) => match keymap.action(key) {
+                Some(action) => {
+                    self.apply(action, keymap);
+                    true
+                }
+                None => false,
+            },
+            InputEvent::Mouse {
+                action,
+                x,
+                y,
+                modifiers,
+            } => match action {
+                MouseAction::Click => match self.item_at(x, y) {
+                    Some(Hit::Item { index, .. }) if modifiers.shift => {
+                        self.extend_to(index);
+                        true
+                    }
+                    Some(Hit::Item { index, .. }) => {
+                        self.select(index);
+                        true
+                    }
+                    _ => false,
+                },
+                MouseAction::ScrollUp => {
+                    self.scroll_up(keymap.scroll_lines);
+                    true
+                }
+                MouseAction::ScrollDown => {
+                    self.scroll_down(keymap.scroll_lines);
+                    true
+                }
+            },
+        }
+    }
+
+    /// Do what `action` says.
+    fn apply(&mut self, action: Action, keymap: &Keymap) {
+        let grid = self.layout.grid.is_some();
+        match action {
+            Action::Up if grid => self.select_up(GridEdge::Stop),
+            Action::Up => self.prev(),
+            Action::Down if grid => self.select_down(GridEdge::Stop),
+            Action::Down => self.next(),
+            Action::Left if grid => self.select_left(GridEdge::Stop),
+            Action::Left => self.scroll_left(keymap.scroll_columns),
+            Action::Right if grid => self.select_right(GridEdge::Stop),
+            Action::Right => self.scroll_right(keymap.scroll_columns),
+            Action::CycleNext => self.cycle_next(),
+            Action::CyclePrev => self.cycle_prev(),
+            Action::First => self.select_first(),
+            Action::Last => self.select_last(),
+            Action::PageUp => self.page_up(),
+            Action::PageDown => self.page_down(),
+            Action::HalfPageUp => self.half_page_up(),
+            Action::HalfPageDown => self.half_page_down(),
+            Action::WindowTop => self.select_window_top(),
+            Action::WindowMiddle => self.select_window_middle(),
+            Action::WindowBottom => self.select_window_bottom(),
+            Action::ScrollUp => self.scroll_up(1),
+            Action::ScrollDown => self.scroll_down(1),
+            Action::LineStart => self.scroll_line_start(),
+            Action::LineEnd => self.scroll_line_end(),
+            Action::PrevLine => self.prev_line(),
+            Action::NextLine => self.next_line(),
+            Action::ToggleMark => self.toggle_mark(),
+            Action::ExtendUp => self.extend_prev(),
+            Action::ExtendDown => self.extend_next(),
+            Action::MarkAll => self.mark_all(),
+            Action::InvertMarks => self.invert_marks(),
+            Action::ClearMarks => self.clear_marks(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+    use super::*;
+    use crate::styled_list::{ListItem, StyledList};
+
+    #[test]
+    fn keymaps() {
+        let mut state = ListState::new(10);
+        let vim = Keymap::vim();
+        assert!(state.handle_event(&'j'.into(), &vim));
+        assert!(state.handle_event(&'j'.into(), &vim));
+        assert_eq!(state.selected(), Some(2));
+        state.handle_event(&'G'.into(), &vim);
+        assert_eq!(state.selected(), Some(9));
+        assert!(!state.handle_event(&'g'.into(), &vim));
+        state.handle_event(&Key::Home.into(), &vim);
+        assert_eq!(state.selected(), Some(0));
+        state.handle_event(&'G'.into(), &vim);
+
+        let emacs = Keymap::emacs();
+        state.handle_event(&KeyPress::from('p').ctrl().into(), &emacs);
+        assert_eq!(state.selected(), Some(8));
+        state.handle_event(&KeyPress::from('<').alt().into(), &emacs);
+        assert_eq!(state.selected(), Some(0));
+
+        let arrows = Keymap::arrows().bind('x', Action::ToggleMark);
+        state.handle_event(&Key::Down.into(), &arrows);
+        state.handle_event(&KeyPress::new(Key::Down).shift().into(), &arrows);
+        assert_eq!(state.marked().collect::<Vec<_>>(), vec![1, 2]);
+        state.handle_event(&'x'.into(), &arrows);
+        assert_eq!(state.marked().collect::<Vec<_>>(), vec![1]);
+        state.handle_event(&Key::End.into(), &arrows);
+        assert_eq!(state.selected(), Some(9));
+    }
+
+    #[test]
+    fn mouse() {
+        let mut items: Vec<ListItem> = (0..10).map(|i| ListItem::new(i.to_string())).collect();
+        items[0] = ListItem::header("h");
+        let area = Rect::new(0, 0, 5, 4);
+        let mut state = ListState::new(10);
+        StatefulWidget::render(
+            StyledList::new(items),
+            area,
+            &mut Buffer::empty(area),
+            &mut state,
+        );
+
+        let mouse = |action, y, shift| InputEvent::Mouse {
+            action,
+            x: 1,
+            y,
+            modifiers: Modifiers {
+                shift,
+                ..Modifiers::default()
+            },
+        };
+        let keymap = Keymap::default();
+        // a header can't be selected
+        assert!(!state.handle_event(&mouse(MouseAction::Click, 0, false), &keymap));
+        assert_eq!(state.selected(), Some(1));
+        assert!(state.handle_event(&mouse(MouseAction::Click, 2, false), &keymap));
+        assert_eq!(state.selected(), Some(2));
+        state.handle_event(&mouse(MouseAction::Click, 3, true), &keymap);
+        assert_eq!(state.marked().collect::<Vec<_>>(), vec![2, 3]);
+        // below the list
+        assert!(!state.handle_event(&mouse(MouseAction::Click, 9, false), &keymap));
+
+        state.handle_event(&mouse(MouseAction::ScrollDown, 0, false), &keymap);
+        assert_eq!(state.scroll_lines, 3);
+    }
+}
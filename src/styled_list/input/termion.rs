@@ -0,0 +1,98 @@
+use termion::event::{Event, Key as TermionKey, MouseButton, MouseEvent};
+
+use super::{InputEvent, Key, KeyPress, Modifiers, MouseAction};
+
+impl InputEvent {
+    /// Convert a termion event. Returns `None` for events that aren't a key press, a left click
+    /// or a turn of the mouse wheel, and for keys without a [`Key`].
+    ///
+    /// Termion doesn't report the modifiers held during mouse events, so shift-click can't be
+    /// told apart from a click. Its one-based mouse positions are made zero-based.
+    pub fn from_termion(event: &Event) -> Option<Self> {
+        match event {
+            Event::Key(key) => key_press(*key).map(InputEvent::Key),
+            Event::Mouse(mouse) => mouse_event(mouse),
+            Event::Unsupported(_) => None,
+        }
+    }
+}
+
+fn key_press(key: TermionKey) -> Option<KeyPress> {
+    let press = match key {
+        TermionKey::Char('\n') => Key::Enter.into(),
+        TermionKey::Char('\t') => Key::Tab.into(),
+        TermionKey::Char(c) => c.into(),
+        TermionKey::Ctrl(c) => KeyPress::from(c).ctrl(),
+        TermionKey::Alt(c) => KeyPress::from(c).alt(),
+        // Terminals send a null byte for ctrl-space
+        TermionKey::Null => KeyPress::from(' ').ctrl(),
+        TermionKey::Up => Key::Up.into(),
+        TermionKey::Down => Key::Down.into(),
+        TermionKey::Left => Key::Left.into(),
+        TermionKey::Right => Key::Right.into(),
+        TermionKey::PageUp => Key::PageUp.into(),
+        TermionKey::PageDown => Key::PageDown.into(),
+        TermionKey::Home => Key::Home.into(),
+        TermionKey::End => Key::End.into(),
+        TermionKey::Esc => Key::Esc.into(),
+        TermionKey::BackTab => Key::BackTab.into(),
+        TermionKey::Backspace => Key::Backspace.into(),
+        TermionKey::Delete => Key::Delete.into(),
+        TermionKey::Insert => Key::Insert.into(),
+        TermionKey::F(n) => Key::F(n).into(),
+        _ => return None,
+    };
+    Some(press)
+}
+
+fn mouse_event(event: &MouseEvent) -> Option<InputEvent> {
+    let MouseEvent::Press(button, x, y) = *event else {
+        return None;
+    };
+    let action = match button {
+        MouseButton::Left => MouseAction::Click,
+        MouseButton::WheelUp => MouseAction::ScrollUp,
+        MouseButton::WheelDown => MouseAction::ScrollDown,
+        _ => return None,
+    };
+    Some(InputEvent::Mouse {
+        action,
+        x: x.saturating_sub(1),
+        y: y.saturating_sub(1),
+        modifiers: Modifiers::default(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts() {
+        let key = |k| InputEvent::from_termion(&Event::Key(k));
+        assert_eq!(key(TermionKey::Char('G')), Some('G'.into()));
+        assert_eq!(
+            key(TermionKey::Ctrl('d')),
+            Some(KeyPress::from('d').ctrl().into())
+        );
+        assert_eq!(key(TermionKey::Char('\n')), Some(Key::Enter.into()));
+        assert_eq!(key(TermionKey::Down), Some(Key::Down.into()));
+        assert_eq!(
+            InputEvent::from_termion(&Event::Unsupported(vec![0x1b])),
+            None
+        );
+
+        let click = Event::Mouse(MouseEvent::Press(MouseButton::Left, 4, 5));
+        assert_eq!(
+            InputEvent::from_termion(&click),
+            Some(InputEvent::Mouse {
+                action: MouseAction::Click,
+                x: 3,
+                y: 4,
+                modifiers: Modifiers::default(),
+            })
+        );
+        let release = Event::Mouse(MouseEvent::Release(4, 5));
+        assert_eq!(InputEvent::from_termion(&release), None);
+    }
+}
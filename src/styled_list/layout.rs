@@ -0,0 +1,164 @@
+use ratatui::layout::Rect;
+
+use super::LineKind;
+
+/// What is drawn at a position of a [`StyledList`](super::StyledList). See
+/// [`ListState::item_at`](super::ListState::item_at).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    /// Line `line` of the [`ListItem`](super::ListItem) at `index`.
+    Item {
+        index: usize,
+        line: usize,
+        column: HitColumn,
+    },
+    /// A section header (see [`ListItem::header`](super::ListItem::header)), which can't be
+    /// selected.
+    Header { index: usize },
+    /// A separator line drawn by [`ItemDisplay::SeparatedBy`](super::ItemDisplay::SeparatedBy),
+    /// between the items at `above` and `below`. At the ends of the list there is no item on one
+    /// side.
+    Separator {
+        above: Option<usize>,
+        below: Option<usize>,
+    },
+}
+
+/// The part of a row in a [`StyledList`](super::StyledList) that a [`Hit`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitColumn {
+    /// The number gutter, see [`StyledList::numbering`](super::StyledList::numbering)
+    Gutter,
+    LeftIndicator,
+    Content,
+    RightIndicator,
+}
+
+/// The layout of the list as of the last render. This maps the rows of the window back to the
+/// lines that were drawn on them.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ListLayout {
+    /// The area the lines were drawn in, not including any block or scrollbar.
+    pub(super) area: Rect,
+    pub(super) gutter_width: u16,
+    pub(super) left_width: u16,
+    pub(super) right_width: u16,
+    /// What was drawn on each row of `area`, from the top. In a grid, this is the first cell of
+    /// each row.
+    pub(super) rows: Vec<LineKind>,
+    pub(super) grid: Option<GridCells>,
+}
+
+/// How the cells of a grid were laid out across the rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct GridCells {
+    pub(super) columns: usize,
+    /// The distance from the start of one cell to the next
+    pub(super) stride: u16,
+    pub(super) width: u16,
+    /// The number of items in the grid
+    pub(super) len: usize,
+}
+
+impl ListLayout {
+    pub(super) fn hit(&self, x: u16, y: u16) -> Option<Hit> {
+        let area = self.area;
+        if x < area.x || x >= area.right() || y < area.y || y >= area.bottom() {
+            return None;
+        }
+
+        match *self.rows.get((y - area.y) as usize)? {
+            LineKind::Item { index, line, .. } => {
+                // the span of columns taken by the item
+                let (index, left, right) = match self.grid {
+                    None => (index, area.x, area.right()),
+                    Some(grid) => {
+                        let column = (x - area.x) / grid.stride.max(1);
+                        let left = area.x + column * grid.stride;
+                        let index = index + column as usize;
+                        if column as usize >= grid.columns
+                            || index >= grid.len
+                            || x >= left + grid.width
+                        {
+                            return None;
+                        }
+                        (index, left, left + grid.width)
+                    }
+                };
+                let gutter = left + self.gutter_width;
+                let column = if x < gutter {
+                    HitColumn::Gutter
+                } else if x < gutter + self.left_width {
+                    HitColumn::LeftIndicator
+                } else if x >= right.saturating_sub(self.right_width) {
+                    HitColumn::RightIndicator
+                } else {
+                    HitColumn::Content
+                };
+                Some(Hit::Item {
+                    index,
+                    line,
+                    column,
+                })
+            }
+            LineKind::Separator { above, below } => Some(Hit::Separator { above, below }),
+            LineKind::Filler => None,
+        }
+    }
+
+    /// The items drawn, in order, and whether all of their lines were drawn.
+    pub(super) fn items(&self) -> Vec<(usize, bool)> {
+        // (index, first line drawn, last line drawn)
+        let mut items: Vec<(usize, bool, bool)> = Vec::new();
+        for row in &self.rows {
+            if let LineKind::Item { index, line, last } = *row {
+                match items.last_mut() {
+                    Some((i, _, end)) if *i == index => *end = last,
+                    _ => items.push((index, line == 0, last)),
+                }
+            }
+        }
+        let grid = self.grid;
+        items
+            .into_iter()
+            .flat_map(|(index, start, end)| {
+                // each row of a grid is a run of items
+                let cells = match grid {
+                    Some(g) => index..g.len.min(index + g.columns),
+                    None => index..index + 1,
+                };
+                cells.map(move |i| (i, start && end))
+            })
+            .collect()
+    }
+
+    /// The number of lines of the item at `index`, if its last line was drawn.
+    pub(super) fn item_lines(&self, index: usize) -> Option<usize> {
+        self.rows.iter().find_map(|row| match *row {
+            LineKind::Item {
+                index: i,
+                line,
+                last: true,
+            } if i == index => Some(line + 1),
+            _ => None,
+        })
+    }
+}
+
+/// The first line drawn in `rows`, as an item and a line within it. When the list is
+/// `separated`, the separator above an item is counted as its first line.
+pub(super) fn anchor(
+    rows: impl IntoIterator<Item = LineKind>,
+    separated: bool,
+) -> Option<(usize, usize)> {
+    rows.into_iter().find_map(|row| match row {
+        LineKind::Item { index, line, .. } => Some((index, line + usize::from(separated))),
+        LineKind::Separator {
+            below: Some(below), ..
+        } => Some((below, 0)),
+        LineKind::Separator {
+            above: Some(above), ..
+        } => Some((above + 1, 0)),
+        _ => None,
+    })
+}
@@ -2,40 +2,72 @@ use std::iter::Enumerate;
 
 use ratatui::{style::Style, text::Spans};
 
-use super::{DisplayLine, LineIndicators, ListItem, Separator};
+use super::{DisplayLine, LineIndicators, LineKind, ListItem, Separator};
 
 /// A struct for iterating through display lines given an item and a selection state
 pub(super) struct ToLines<'a> {
+    index: usize,
     style: Style,
     text_items: Enumerate<std::vec::IntoIter<Spans<'a>>>,
     //text_items: VecDeque<(usize, usize, Spans<'a>)>,
     indicators: LineIndicators,
     selected: bool,
+    header: bool,
     line_count: usize,
+    cursor: Option<Cursor>,
+}
+
+/// The line cursor of the selected item, see [`ToLines::cursor`].
+struct Cursor {
+    line: usize,
+    style: Style,
+    only: bool,
 }
 
 impl<'a> ToLines<'a> {
-    pub(super) fn new(item: ListItem<'a>, selected: bool) -> Self {
+    pub(super) fn new(item: ListItem<'a>, index: usize, selected: bool) -> Self {
         let line_count = item.height();
         let text_items = item.content.lines.into_iter().enumerate();
 
         //let text_items = VecDeque::from_iter(text_items);
         Self {
+            index,
             style: item.style,
             text_items,
             indicators: item.indicators,
             selected,
+            header: item.header,
             line_count,
+            cursor: None,
         }
     }
 
+    pub(super) fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Put the line cursor on `line`, or the last line if there aren't that many. The cursor line
+    /// has `style` patched in, and if `only` is set it is the only line of the item that must be
+    /// displayed.
+    pub(super) fn cursor(mut self, line: usize, style: Style, only: bool) -> Self {
+        self.cursor = Some(Cursor {
+            line: line.min(self.line_count.saturating_sub(1)),
+            style,
+            only,
+        });
+        self
+    }
+
     pub(super) fn empty_with_selection(selected: bool) -> Self {
         Self {
+            index: 0,
             style: Style::default(),
             text_items: Vec::new().into_iter().enumerate(),
             selected,
+            header: false,
             indicators: LineIndicators::default(),
             line_count: 0,
+            cursor: None,
         }
     }
 }
@@ -44,12 +76,31 @@ impl<'a> Iterator for ToLines<'a> {
     type Item = DisplayLine<'a>;
     fn next(&mut self) -> Option<Self::Item> {
         let (i, line) = self.text_items.next()?;
+        let (style, must_display) = match &self.cursor {
+            Some(c) if c.line == i => (self.style.patch(c.style), self.selected),
+            Some(c) => (self.style, self.selected && !c.only),
+            None => (self.style, self.selected),
+        };
         let res = DisplayLine {
-            style: self.style,
+            style,
             line,
-            must_display: self.selected,
-            left_indicator: self.indicators.left.fill_char(i, self.line_count).into(),
-            right_indicator: self.indicators.right.fill_char(i, self.line_count).into(),
+            must_display,
+            kind: LineKind::Item {
+                index: self.index,
+                line: i,
+                last: i + 1 == self.line_count,
+            },
+            left_indicator: self
+                .indicators
+                .left
+                .fill(i, self.line_count, self.selected)
+                .into(),
+            right_indicator: self
+                .indicators
+                .right
+                .fill(i, self.line_count, self.selected)
+                .into(),
+            header: self.header,
         };
         Some(res)
     }
@@ -93,6 +144,7 @@ where
     lines_iter: Option<ToLines<'a>>,
     separator: Separator,
     prev_was_selected: bool,
+    prev_index: Option<usize>,
 }
 
 impl<'a, I> Separated<'a, I>
@@ -111,6 +163,7 @@ where
             lines_iter,
             separator,
             prev_was_selected: false,
+            prev_index: None,
         }
     }
 }
@@ -137,12 +190,23 @@ where
                     // the separator line is marked "must_display" also.
                     let must_display = next.selected || self.prev_was_selected;
                     let next_style = next.style;
+                    let kind = LineKind::Separator {
+                        above: self.prev_index,
+                        below: Some(next.index),
+                    };
+                    self.prev_index = Some(next.index);
                     self.lines_iter = Some(next);
-                    self.separator.display_line(must_display, Some(next_style))
+                    self.separator
+                        .display_line(must_display, Some(next_style), kind)
                 }
                 None => {
                     self.lines_iter = None;
-                    self.separator.display_line(self.prev_was_selected, None)
+                    let kind = LineKind::Separator {
+                        above: self.prev_index,
+                        below: None,
+                    };
+                    self.separator
+                        .display_line(self.prev_was_selected, None, kind)
                 }
             },
         };
@@ -153,16 +217,24 @@ where
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use ratatui::{style::Color, symbols::bar::HALF};
 
+    use crate::styled_list::HalfBlock;
+
     use super::*;
 
+    fn half_block() -> Separator {
+        Separator::new(Arc::new(HalfBlock), 1, Style::default())
+    }
+
     #[test]
     fn to_lines() {
         let style = Style::default().fg(Color::Red).bg(Color::Blue);
         let it = ListItem::new("a\nb\nc").style(style);
 
-        for (dl, s) in ToLines::new(it, false).zip(["a", "b", "c"]) {
+        for (dl, s) in ToLines::new(it, 0, false).zip(["a", "b", "c"]) {
             assert_eq!(dl.line, Spans::from(s));
             assert_eq!(dl.style, style);
         }
@@ -172,7 +244,7 @@ mod test {
     fn to_lines_selected() {
         let item = ListItem::new("a\nb");
 
-        for i in ToLines::new(item, true) {
+        for i in ToLines::new(item, 0, true) {
             assert!(i.must_display)
         }
     }
@@ -180,8 +252,8 @@ mod test {
     #[test]
     fn basic_display_lines() {
         let items = vec![
-            ToLines::new(ListItem::new("a\nb\nc"), false),
-            ToLines::new(ListItem::new("d\ne"), true),
+            ToLines::new(ListItem::new("a\nb\nc"), 0, false),
+            ToLines::new(ListItem::new("d\ne"), 1, true),
         ];
         for (dl, (t, s)) in Basic::new(items).zip([
             ("a", false),
@@ -199,21 +271,19 @@ mod test {
     fn separated_display_lines_end_selected() {
         let sstyle = Style::default().bg(Color::Red).fg(Color::Blue);
         let items = vec![
-            ToLines::new(ListItem::new("a\nb\nc"), false),
-            ToLines::new(ListItem::new("d\ne").style(sstyle), true),
+            ToLines::new(ListItem::new("a\nb\nc"), 0, false),
+            ToLines::new(ListItem::new("d\ne").style(sstyle), 1, true),
         ];
-        for (dl, (t, s, bg, fg)) in
-            Separated::new(items, Separator::new(1, Style::default())).zip([
-                (HALF, false, None, None),
-                ("a", false, None, None),
-                ("b", false, None, None),
-                ("c", false, None, None),
-                (HALF, true, None, Some(Color::Red)),
-                ("d", true, Some(Color::Red), Some(Color::Blue)),
-                ("e", true, Some(Color::Red), Some(Color::Blue)),
-                (HALF, true, Some(Color::Red), None),
-            ])
-        {
+        for (dl, (t, s, bg, fg)) in Separated::new(items, half_block()).zip([
+            (HALF, false, None, None),
+            ("a", false, None, None),
+            ("b", false, None, None),
+            ("c", false, None, None),
+            (HALF, true, None, Some(Color::Red)),
+            ("d", true, Some(Color::Red), Some(Color::Blue)),
+            ("e", true, Some(Color::Red), Some(Color::Blue)),
+            (HALF, true, Some(Color::Red), None),
+        ]) {
             assert_eq!(dl.line, Spans::from(t));
             assert_eq!(dl.must_display, s);
             assert_eq!(dl.style.bg, bg);
@@ -225,22 +295,20 @@ mod test {
     fn separated_display_lines_begin_selected() {
         let sstyle = Style::default().bg(Color::Red).fg(Color::Blue);
         let mut items = vec![
-            ToLines::new(ListItem::new("a\nb\nc").style(sstyle), true),
-            ToLines::new(ListItem::new("d\ne"), false),
+            ToLines::new(ListItem::new("a\nb\nc").style(sstyle), 0, true),
+            ToLines::new(ListItem::new("d\ne"), 1, false),
         ];
         items[0].selected = true;
-        for (dl, (t, s, bg, fg)) in
-            Separated::new(items, Separator::new(1, Style::default())).zip([
-                (HALF, true, None, Some(Color::Red)),
-                ("a", true, Some(Color::Red), Some(Color::Blue)),
-                ("b", true, Some(Color::Red), Some(Color::Blue)),
-                ("c", true, Some(Color::Red), Some(Color::Blue)),
-                (HALF, true, Some(Color::Red), None),
-                ("d", false, None, None),
-                ("e", false, None, None),
-                (HALF, false, None, None),
-            ])
-        {
+        for (dl, (t, s, bg, fg)) in Separated::new(items, half_block()).zip([
+            (HALF, true, None, Some(Color::Red)),
+            ("a", true, Some(Color::Red), Some(Color::Blue)),
+            ("b", true, Some(Color::Red), Some(Color::Blue)),
+            ("c", true, Some(Color::Red), Some(Color::Blue)),
+            (HALF, true, Some(Color::Red), None),
+            ("d", false, None, None),
+            ("e", false, None, None),
+            (HALF, false, None, None),
+        ]) {
             assert_eq!(dl.line, Spans::from(t));
             assert_eq!(dl.must_display, s);
             assert_eq!(dl.style.bg, bg);
@@ -252,25 +320,23 @@ mod test {
     fn separated_display_lines_middle_selected() {
         let sstyle = Style::default().bg(Color::Red).fg(Color::Blue);
         let items = vec![
-            ToLines::new(ListItem::new("a\nb\nc"), false),
-            ToLines::new(ListItem::new("d\ne").style(sstyle), true),
-            ToLines::new(ListItem::new("f\ng"), false),
+            ToLines::new(ListItem::new("a\nb\nc"), 0, false),
+            ToLines::new(ListItem::new("d\ne").style(sstyle), 1, true),
+            ToLines::new(ListItem::new("f\ng"), 2, false),
         ];
-        for (dl, (t, s, bg, fg)) in
-            Separated::new(items, Separator::new(1, Style::default())).zip([
-                (HALF, false, None, None),
-                ("a", false, None, None),
-                ("b", false, None, None),
-                ("c", false, None, None),
-                (HALF, true, None, Some(Color::Red)),
-                ("d", true, Some(Color::Red), Some(Color::Blue)),
-                ("e", true, Some(Color::Red), Some(Color::Blue)),
-                (HALF, true, Some(Color::Red), None),
-                ("f", false, None, None),
-                ("g", false, None, None),
-                (HALF, false, None, None),
-            ])
-        {
+        for (dl, (t, s, bg, fg)) in Separated::new(items, half_block()).zip([
+            (HALF, false, None, None),
+            ("a", false, None, None),
+            ("b", false, None, None),
+            ("c", false, None, None),
+            (HALF, true, None, Some(Color::Red)),
+            ("d", true, Some(Color::Red), Some(Color::Blue)),
+            ("e", true, Some(Color::Red), Some(Color::Blue)),
+            (HALF, true, Some(Color::Red), None),
+            ("f", false, None, None),
+            ("g", false, None, None),
+            (HALF, false, None, None),
+        ]) {
             assert_eq!(dl.line, Spans::from(t));
             assert_eq!(dl.must_display, s, "line: {:?}", dl);
             assert_eq!(dl.style.bg, bg);
@@ -284,26 +350,24 @@ mod test {
         let sstyle = Style::default().bg(Color::Red).fg(Color::Blue);
         let lstyle = Style::default().bg(Color::Green);
         let mut items = vec![
-            ToLines::new(ListItem::new("a\nb\nc").style(fstyle), false),
-            ToLines::new(ListItem::new("d\ne").style(sstyle), true),
-            ToLines::new(ListItem::new("f\ng").style(lstyle), false),
+            ToLines::new(ListItem::new("a\nb\nc").style(fstyle), 0, false),
+            ToLines::new(ListItem::new("d\ne").style(sstyle), 1, true),
+            ToLines::new(ListItem::new("f\ng").style(lstyle), 2, false),
         ];
         items[1].selected = true;
-        for (dl, (t, s, bg, fg)) in
-            Separated::new(items, Separator::new(1, Style::default())).zip([
-                (HALF, false, None, Some(Color::Cyan)),
-                ("a", false, Some(Color::Cyan), None),
-                ("b", false, Some(Color::Cyan), None),
-                ("c", false, Some(Color::Cyan), None),
-                (HALF, true, Some(Color::Cyan), Some(Color::Red)),
-                ("d", true, Some(Color::Red), Some(Color::Blue)),
-                ("e", true, Some(Color::Red), Some(Color::Blue)),
-                (HALF, true, Some(Color::Red), Some(Color::Green)),
-                ("f", false, Some(Color::Green), None),
-                ("g", false, Some(Color::Green), None),
-                (HALF, false, Some(Color::Green), None),
-            ])
-        {
+        for (dl, (t, s, bg, fg)) in Separated::new(items, half_block()).zip([
+            (HALF, false, None, Some(Color::Cyan)),
+            ("a", false, Some(Color::Cyan), None),
+            ("b", false, Some(Color::Cyan), None),
+            ("c", false, Some(Color::Cyan), None),
+            (HALF, true, Some(Color::Cyan), Some(Color::Red)),
+            ("d", true, Some(Color::Red), Some(Color::Blue)),
+            ("e", true, Some(Color::Red), Some(Color::Blue)),
+            (HALF, true, Some(Color::Red), Some(Color::Green)),
+            ("f", false, Some(Color::Green), None),
+            ("g", false, Some(Color::Green), None),
+            (HALF, false, Some(Color::Green), None),
+        ]) {
             assert_eq!(dl.line, Spans::from(t));
             assert_eq!(dl.must_display, s, "line: {:?}", dl);
             assert_eq!(dl.style.bg, bg);
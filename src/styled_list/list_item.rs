@@ -1,4 +1,47 @@
-use ratatui::{style::Style, text::Text};
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use ratatui::{
+    style::Style,
+    text::{Span, Text},
+};
+#[cfg(feature = "serde")]
+use serde_derive::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+/// A stable identity for a [`ListItem`], e.g. a message id. See [`ListItem::key`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum ItemKey {
+    Int(u64),
+    Str(String),
+}
+
+impl From<u64> for ItemKey {
+    fn from(key: u64) -> Self {
+        ItemKey::Int(key)
+    }
+}
+
+impl From<usize> for ItemKey {
+    fn from(key: usize) -> Self {
+        ItemKey::Int(key as u64)
+    }
+}
+
+impl From<&str> for ItemKey {
+    fn from(key: &str) -> Self {
+        ItemKey::Str(key.to_string())
+    }
+}
+
+impl From<String> for ItemKey {
+    fn from(key: String) -> Self {
+        ItemKey::Str(key)
+    }
+}
 
 /// An Item in the list
 #[derive(Debug, Clone, PartialEq)]
@@ -6,6 +49,8 @@ pub struct ListItem<'a> {
     pub(super) content: Text<'a>,
     pub(super) style: Style,
     pub(super) indicators: LineIndicators,
+    pub(super) header: bool,
+    pub(super) key: Option<ItemKey>,
 }
 
 impl<'a> ListItem<'a> {
@@ -17,9 +62,29 @@ impl<'a> ListItem<'a> {
             content: content.into(),
             style: Style::default(),
             indicators: LineIndicators::default(),
+            header: false,
+            key: None,
         }
     }
 
+    /// Create a section header. Headers are styled with the list's
+    /// [`header_style`](super::StyledList::header_style) and can't be selected. Every item after
+    /// a header (until the next one) is in its section.
+    pub fn header<T>(content: T) -> ListItem<'a>
+    where
+        T: Into<Text<'a>>,
+    {
+        ListItem {
+            header: true,
+            ..ListItem::new(content)
+        }
+    }
+
+    /// Is this item a section header?
+    pub fn is_header(&self) -> bool {
+        self.header
+    }
+
     /// Set the style for this item. This style will be patched into the default style, and will
     /// have selected style patched into it.
     pub fn style(mut self, style: Style) -> Self {
@@ -32,6 +97,15 @@ impl<'a> ListItem<'a> {
         self.content.height()
     }
 
+    /// Give the item a key that stays the same when items are added or removed around it. While
+    /// the selected item has a key, the [`ListState`](super::ListState) keeps the selection (and
+    /// the window) on the same item across renders, even if it moves to another index. If the
+    /// item is removed, the item that was after it (or else before it) is selected.
+    pub fn key<K: Into<ItemKey>>(mut self, key: K) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
     /// set the indicators for this item. These will be replaced with the lists's
     /// selected_indicator if it has been set and the item is selected.
     pub fn indicators(mut self, indicators: LineIndicators) -> Self {
@@ -41,7 +115,7 @@ impl<'a> ListItem<'a> {
 }
 
 /// Container for holding the [Indicator]s for the left and right indicator columns
-#[derive(Copy, Clone, Default, PartialEq, Debug, Eq)]
+#[derive(Clone, Default, PartialEq, Debug, Eq)]
 pub struct LineIndicators {
     pub(super) left: Indicator,
     pub(super) right: Indicator,
@@ -57,14 +131,34 @@ impl LineIndicators {
         self.right = right;
         self
     }
+
+    /// The widths reserved for the left and right indicators, see [`Indicator::width`].
+    pub(super) fn widths(&self) -> (usize, usize) {
+        (self.left.width(), self.right.width())
+    }
+
+    /// Replace any [`Indicator::Spinner`] with its frame for `frame`.
+    pub(super) fn at_frame(self, frame: usize) -> Self {
+        LineIndicators {
+            left: self.left.at_frame(frame),
+            right: self.right.at_frame(frame),
+        }
+    }
 }
 
+/// The function of an [`Indicator::Custom`]. It is called with the index of the line, the number
+/// of lines in the item, and whether the item is selected.
+pub type IndicatorFn = dyn Fn(usize, usize, bool) -> Span<'static> + Send + Sync;
+
 /// An indicator for an item.
 ///
-/// Each indicator is a single column wide, and used to decorate a [ListItem] that is displayed.
-/// Since a [`ListItem`] may be multiple lines, various strategies are available for how to display
-/// the indicator - see the variants for details
-#[derive(Copy, Clone, PartialEq, Debug, Eq)]
+/// Indicators are used to decorate a [ListItem] that is displayed. Since a [`ListItem`] may be
+/// multiple lines, various strategies are available for how to display the indicator - see the
+/// variants for details.
+///
+/// An indicator can be several columns wide, e.g. `"[x]"`. Each indicator column is as wide as
+/// the widest indicator drawn in it.
+#[derive(Clone)]
 pub enum Indicator {
     /// each line of text for the item will display this char in the indicator column
     Char(&'static str),
@@ -76,13 +170,33 @@ pub enum Indicator {
     /// idx is greater than the number of lines to be displayed, the last line of text will display
     /// the indicator char.
     IdxOrLast(usize, &'static str),
+    /// Another indicator, with this style patched into the style of the item's line.
+    Styled(Box<Indicator>, Style),
+    /// Each line displays whatever the function returns. See [`IndicatorFn`].
+    Custom(Arc<IndicatorFn>),
+    /// Every line displays one of the frames, starting over after the last one. The frame is
+    /// advanced by [`ListState::tick`](super::ListState::tick), e.g. to show an item is loading.
+    Spinner(&'static [&'static str]),
 }
 
 impl Indicator {
-    /// Get the indicator char for the line. The `lines` parameter is used to determine last line.
-    pub(crate) fn fill_char(&self, line_idx: usize, lines: usize) -> &'static str {
+    /// An [`Indicator::Custom`] calling `f`.
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(usize, usize, bool) -> Span<'static> + Send + Sync + 'static,
+    {
+        Indicator::Custom(Arc::new(f))
+    }
+
+    /// This indicator, with `style` patched into the style of the item's line.
+    pub fn style(self, style: Style) -> Self {
+        Indicator::Styled(Box::new(self), style)
+    }
+
+    /// Get the indicator for the line. The `lines` parameter is used to determine last line.
+    pub(crate) fn fill(&self, line_idx: usize, lines: usize, selected: bool) -> Span<'static> {
         use Indicator::*;
-        match *self {
+        let c = match *self {
             Char(c) => c,
             FirstLine(c) => {
                 if line_idx == 0 {
@@ -105,6 +219,36 @@ impl Indicator {
                     " "
                 }
             }
+            Styled(ref indicator, style) => {
+                let mut span = indicator.fill(line_idx, lines, selected);
+                span.style = span.style.patch(style);
+                return span;
+            }
+            Custom(ref f) => return f(line_idx, lines, selected),
+            Spinner(frames) => frames.first().copied().unwrap_or(" "),
+        };
+        Span::raw(c)
+    }
+
+    /// The width of the indicator on the first line of a selected item, or the widest frame of
+    /// a spinner. This is the width reserved for the indicator before the lines are drawn.
+    pub(super) fn width(&self) -> usize {
+        match self {
+            Indicator::Styled(indicator, _) => indicator.width(),
+            Indicator::Spinner(frames) => frames.iter().map(|f| f.width()).max().unwrap_or(0),
+            _ => self.fill(0, 1, true).width(),
+        }
+    }
+
+    fn at_frame(self, frame: usize) -> Self {
+        match self {
+            Indicator::Spinner(frames) if !frames.is_empty() => {
+                Indicator::Char(frames[frame % frames.len()])
+            }
+            Indicator::Styled(indicator, style) => {
+                Indicator::Styled(Box::new(indicator.at_frame(frame)), style)
+            }
+            other => other,
         }
     }
 }
@@ -114,3 +258,35 @@ impl Default for Indicator {
         Indicator::Char(" ")
     }
 }
+
+impl PartialEq for Indicator {
+    fn eq(&self, other: &Self) -> bool {
+        use Indicator::*;
+        match (self, other) {
+            (Char(a), Char(b)) | (LastLine(a), LastLine(b)) | (FirstLine(a), FirstLine(b)) => {
+                a == b
+            }
+            (IdxOrLast(i, a), IdxOrLast(j, b)) => i == j && a == b,
+            (Styled(a, s), Styled(b, t)) => a == b && s == t,
+            (Custom(a), Custom(b)) => Arc::ptr_eq(a, b),
+            (Spinner(a), Spinner(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Indicator {}
+
+impl Debug for Indicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indicator::Char(c) => f.debug_tuple("Char").field(c).finish(),
+            Indicator::LastLine(c) => f.debug_tuple("LastLine").field(c).finish(),
+            Indicator::FirstLine(c) => f.debug_tuple("FirstLine").field(c).finish(),
+            Indicator::IdxOrLast(i, c) => f.debug_tuple("IdxOrLast").field(i).field(c).finish(),
+            Indicator::Styled(i, s) => f.debug_tuple("Styled").field(i).field(s).finish(),
+            Indicator::Custom(_) => f.write_str("Custom(..)"),
+            Indicator::Spinner(frames) => f.debug_tuple("Spinner").field(frames).finish(),
+        }
+    }
+}
@@ -1,31 +1,192 @@
-use std::cmp::min;
+use std::{cmp::min, collections::BTreeSet, ops::Range};
 
 #[cfg(feature = "serde")]
 use serde_derive::{Deserialize, Serialize};
 
+use super::{
+    gutter,
+    layout::{Hit, ListLayout},
+    ItemKey, LineKind,
+};
+
 /// State for a [`StyledList`](super::StyledList)
 ///
 /// This state tracks the selected item in a list, and provides methods for cycling the list.
 /// The size of the list is the number of [`ListItem`](super::ListItem)s to cycle through.
 ///
-/// panics if created or resized to have a size of 0
+/// In addition to the selected item (the cursor), any number of items can be marked. Marked
+/// items are drawn with the selected styling, while the cursor alone controls which lines the
+/// window keeps on screen.
+///
+/// When the list is filtered (see [`ListState::set_filter`]), indices are still those of the
+/// unfiltered list, so clearing the filter keeps the same item selected. Navigation skips over
+/// items that don't match the filter.
+///
+/// Section headers (see [`ListItem::header`](super::ListItem::header)) can't be selected or
+/// marked, and navigation skips over them. The headers are found when the list is rendered.
+///
+/// A list can be empty, in which case nothing is selected and navigation does nothing. Resizing
+/// an empty list selects the first item.
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct ListState {
     pub(super) size: usize,
-    pub(super) selected: usize,
+    /// The selected item, which is `None` only if the list is empty. This was saved as an index
+    /// before empty lists were allowed, and either form can be deserialized.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "deserialize_selected")
+    )]
+    pub(super) selected: Option<usize>,
     pub(super) window_first: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) marked: BTreeSet<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) anchor: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) h_offset: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) h_request: Option<HScroll>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) h_limit: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) scroll_request: Option<(usize, Align)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) scroll_lines: isize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) pin: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) window_len: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) total_lines: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) filter: String,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) matches: Option<Vec<usize>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) headers: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) layout: ListLayout,
+    /// The first line shown by the last render, as an item and a line within it. This keeps the
+    /// window in place for lists that don't render from the first item.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) window_anchor: (usize, usize),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) frame: usize,
+    /// The items given hint keys by the last render, in the order of the keys.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) hints: Vec<usize>,
+    /// Is the selection following the end of the list? See
+    /// [`WindowType::Follow`](super::WindowType::Follow).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) following: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) new_items: usize,
+    /// The line cursor, as an item and a line in it. The cursor is only used while that item is
+    /// selected, and moves back to the first line when another item is rendered as selected.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) line_cursor: (usize, usize),
+    /// The number of lines in the selected item during the last render
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) selected_lines: usize,
+    /// The keys around the selection as of the last render, if the selected item has a key. See
+    /// [`ListItem::key`](super::ListItem::key).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(super) keys: Option<TrackedKeys>,
+}
+
+/// The keys used to find the selection and the window again after the items move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub(super) struct TrackedKeys {
+    /// The index of the selection when the keys were recorded. If the selection has been moved
+    /// since, it isn't moved back to the key.
+    index: usize,
+    selected: ItemKey,
+    /// The keys of the nearest keyed items after and before the selection, in case the selected
+    /// item is removed
+    after: Option<ItemKey>,
+    before: Option<ItemKey>,
+    /// The key of the first item in the window
+    anchor: Option<ItemKey>,
+}
+
+/// Horizontal scroll positions that can only be worked out while rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HScroll {
+    /// Show the end of the widest line in the window
+    LineEnd,
+    /// Show the end of the widest line of the selected item
+    SelectedTail,
+}
+
+/// Where [`ListState::scroll_to`] places an item in the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    /// On the first row of the window
+    Top,
+    /// On the middle row of the window
+    Center,
+    /// On the last row of the window
+    Bottom,
+}
+
+/// What the grid navigation of a [`ListState`] does when the selection is at the edge of the
+/// grid, e.g. [`ListState::select_right`] on the last item of a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridEdge {
+    /// Stay on the item at the edge
+    Stop,
+    /// Move to the other end of the same row or column
+    Wrap,
+    /// Move on to the next (or previous) row or column, as if the grid was read in order. Left
+    /// and right continue onto the adjacent row, up and down onto the adjacent column.
+    Continue,
+}
+
+impl Align {
+    /// The row of a window `height` rows tall.
+    fn row(self, height: usize) -> usize {
+        match self {
+            Align::Top => 0,
+            Align::Center => height / 2,
+            Align::Bottom => height.saturating_sub(1),
+        }
+    }
 }
 
 impl ListState {
     /// Create a new state for a list of length `size`.
     pub fn new(size: usize) -> Self {
         let mut res = ListState {
-            size: 1,
-            selected: 0,
+            size: 0,
+            selected: None,
             window_first: 0,
+            marked: BTreeSet::new(),
+            anchor: None,
+            h_offset: 0,
+            h_request: None,
+            h_limit: None,
+            scroll_request: None,
+            scroll_lines: 0,
+            pin: None,
+            window_len: 0,
+            total_lines: None,
+            filter: String::new(),
+            matches: None,
+            headers: Vec::new(),
+            layout: ListLayout::default(),
+            window_anchor: (0, 0),
+            frame: 0,
+            hints: Vec::new(),
+            following: false,
+            new_items: 0,
+            line_cursor: (0, 0),
+            selected_lines: 0,
+            keys: None,
         };
         res.resize(size);
+        res.new_items = 0;
         res
     }
 
@@ -34,54 +195,860 @@ impl ListState {
         self.window_first = pos;
     }
 
+    /// Record the number of lines displayed in the window, and the total number of display
+    /// lines in the list if it was counted.
+    pub(super) fn set_window(&mut self, len: usize, total: Option<usize>) {
+        self.window_len = len;
+        self.total_lines = total;
+    }
+
+    /// The range of display lines shown in the window during the last render.
+    pub fn visible_lines(&self) -> Range<usize> {
+        self.window_first..self.window_first + self.window_len
+    }
+
+    /// The total number of display lines (including separators) in the list during the last
+    /// render. This is only counted when the list needs it, e.g. to draw a
+    /// [`Scrollbar`](super::Scrollbar), otherwise it is `None`.
+    pub fn total_lines(&self) -> Option<usize> {
+        self.total_lines
+    }
+
+    /// The number of rows in the window during the last render.
+    pub fn window_height(&self) -> usize {
+        self.layout.area.height as usize
+    }
+
+    /// The range of [ListItems](super::ListItem) with any lines shown in the window during the
+    /// last render.
+    pub fn visible_items(&self) -> Range<usize> {
+        // a bottom up list has the items in reverse
+        let items = self.layout.items().into_iter().map(|(i, _)| i);
+        match (items.clone().min(), items.max()) {
+            (Some(first), Some(last)) => first..last + 1,
+            _ => 0..0,
+        }
+    }
+
+    /// The number of items to move for a page: the number of items shown in full during the
+    /// last render.
+    fn page_size(&self) -> usize {
+        let full = self.layout.items().iter().filter(|(_, full)| *full).count();
+        full.max(1)
+    }
+
+    /// Move the selection to the item `f` finds from the selected item. Nothing happens if `f`
+    /// finds no item, or if the list is empty.
+    fn move_selection(&mut self, f: impl FnOnce(&Self, usize) -> Option<usize>) {
+        if let Some(n) = self.selected.and_then(|selected| f(self, selected)) {
+            self.selected = Some(n);
+        }
+    }
+
+    /// Move the selection `n` selectable items forward, stopping at the last one.
+    fn step_next(&mut self, n: usize) {
+        for _ in 0..n {
+            self.move_selection(Self::next_selectable);
+        }
+    }
+
+    /// Move the selection `n` selectable items back, stopping at the first one.
+    fn step_prev(&mut self, n: usize) {
+        for _ in 0..n {
+            self.move_selection(Self::prev_selectable);
+        }
+    }
+
+    /// Select the item a window lower in the list. The page size is the number of items
+    /// shown in full during the last render, so it works the same for items of any height.
+    pub fn page_down(&mut self) {
+        self.step_next(self.page_size());
+    }
+
+    /// Select the item a window higher in the list. See [`ListState::page_down`].
+    pub fn page_up(&mut self) {
+        self.step_prev(self.page_size());
+    }
+
+    /// Select the item half a window lower in the list. See [`ListState::page_down`].
+    pub fn half_page_down(&mut self) {
+        self.step_next(self.page_size().div_ceil(2));
+    }
+
+    /// Select the item half a window higher in the list. See [`ListState::page_down`].
+    pub fn half_page_up(&mut self) {
+        self.step_prev(self.page_size().div_ceil(2));
+    }
+
+    /// Select the first item in the list.
+    pub fn select_first(&mut self) {
+        if self.size == 0 {
+            return;
+        }
+        self.selected = Some(0);
+        if !self.is_selectable(0) {
+            self.step_next(1);
+        }
+    }
+
+    /// Select the last item in the list.
+    pub fn select_last(&mut self) {
+        if let Some(last) = self.prev_selectable(self.size) {
+            self.selected = Some(last);
+        }
+    }
+
+    /// Select the first item shown in full at the top of the window during the last render.
+    pub fn select_window_top(&mut self) {
+        let items = self.layout.items();
+        let top = items
+            .iter()
+            .find(|&&(i, full)| full && self.is_selectable(i));
+        if let Some(&(i, _)) = top {
+            self.selected = Some(i);
+        }
+    }
+
+    /// Select the item shown in the middle row of the window during the last render. If that
+    /// row isn't a selectable item, the next item that is gets selected.
+    pub fn select_window_middle(&mut self) {
+        let rows = &self.layout.rows;
+        let middle = rows[rows.len() / 2..].iter().find_map(|row| match *row {
+            LineKind::Item { index, .. } if self.is_selectable(index) => Some(index),
+            _ => None,
+        });
+        if let Some(i) = middle {
+            self.selected = Some(i);
+        }
+    }
+
+    /// Select the last item shown in full at the bottom of the window during the last render.
+    pub fn select_window_bottom(&mut self) {
+        let items = self.layout.items();
+        let bottom = items
+            .iter()
+            .rev()
+            .find(|&&(i, full)| full && self.is_selectable(i));
+        if let Some(&(i, _)) = bottom {
+            self.selected = Some(i);
+        }
+    }
+
+    /// The number of columns of the grid during the last render. Lists that aren't a grid have
+    /// a single column.
+    fn columns(&self) -> usize {
+        self.layout.grid.map_or(1, |g| g.columns)
+    }
+
+    /// The item in `column` of the last row of the grid that has one.
+    fn column_bottom(&self, column: usize) -> usize {
+        let columns = self.columns();
+        let last_row = (self.size - 1) / columns * columns;
+        match last_row + column {
+            i if i < self.size => i,
+            i => i.saturating_sub(columns),
+        }
+    }
+
+    /// Select the item to the left of the selection in a grid
+    /// ([`ItemDisplay::Grid`](super::ItemDisplay::Grid)), with `edge` controlling what happens
+    /// in the first column. The grid navigation uses the number of columns of the last render.
+    pub fn select_left(&mut self, edge: GridEdge) {
+        self.move_selection(|s, selected| {
+            let columns = s.columns();
+            match edge {
+                _ if selected % columns > 0 => Some(selected - 1),
+                GridEdge::Stop => None,
+                GridEdge::Wrap => Some(min(selected + columns, s.size) - 1),
+                GridEdge::Continue => selected.checked_sub(1),
+            }
+        });
+    }
+
+    /// Select the item to the right of the selection in a grid. See [`ListState::select_left`].
+    pub fn select_right(&mut self, edge: GridEdge) {
+        self.move_selection(|s, selected| {
+            let columns = s.columns();
+            let row_start = selected / columns * columns;
+            let row_end = min(row_start + columns, s.size);
+            match edge {
+                _ if selected + 1 < row_end => Some(selected + 1),
+                GridEdge::Stop => None,
+                GridEdge::Wrap => Some(row_start),
+                GridEdge::Continue => (selected + 1 < s.size).then_some(selected + 1),
+            }
+        });
+    }
+
+    /// Select the item above the selection in a grid. See [`ListState::select_left`].
+    pub fn select_up(&mut self, edge: GridEdge) {
+        self.move_selection(|s, selected| {
+            let columns = s.columns();
+            let column = selected % columns;
+            match edge {
+                _ if selected >= columns => Some(selected - columns),
+                GridEdge::Stop => None,
+                GridEdge::Wrap => Some(s.column_bottom(column)),
+                GridEdge::Continue => column.checked_sub(1).map(|c| s.column_bottom(c)),
+            }
+        });
+    }
+
+    /// Select the item below the selection in a grid. If the row below is too short to have an
+    /// item in the same column, its last item is selected. See [`ListState::select_left`].
+    pub fn select_down(&mut self, edge: GridEdge) {
+        self.move_selection(|s, selected| {
+            let columns = s.columns();
+            let column = selected % columns;
+            let last_row = (s.size - 1) / columns;
+            match edge {
+                _ if selected / columns < last_row => Some(min(selected + columns, s.size - 1)),
+                GridEdge::Stop => None,
+                GridEdge::Wrap => Some(column),
+                GridEdge::Continue => (column + 1 < min(columns, s.size)).then_some(column + 1),
+            }
+        });
+    }
+
+    /// Was the item at index `n` a section header in the last render?
+    fn is_header(&self, n: usize) -> bool {
+        self.headers.binary_search(&n).is_ok()
+    }
+
+    /// Can the item at index `n` be selected? Headers and items hidden by the filter can't be.
+    pub(super) fn is_selectable(&self, n: usize) -> bool {
+        let shown = match &self.matches {
+            Some(m) => m.binary_search(&n).is_ok(),
+            None => n < self.size,
+        };
+        shown && !self.is_header(n)
+    }
+
+    /// The first selectable item after `n`.
+    fn next_selectable(&self, mut n: usize) -> Option<usize> {
+        loop {
+            n = match &self.matches {
+                Some(m) => m.get(m.partition_point(|&i| i <= n)).copied(),
+                None => (n + 1 < self.size).then_some(n + 1),
+            }?;
+            if !self.is_header(n) {
+                return Some(n);
+            }
+        }
+    }
+
+    /// The last selectable item before `n`.
+    fn prev_selectable(&self, mut n: usize) -> Option<usize> {
+        loop {
+            n = match &self.matches {
+                Some(m) => m[..m.partition_point(|&i| i < n)].last().copied(),
+                None => n.checked_sub(1),
+            }?;
+            if !self.is_header(n) {
+                return Some(n);
+            }
+        }
+    }
+
+    /// The selectable item closest to `n`, preferring the one before it on a tie.
+    fn nearest_selectable(&self, n: usize) -> Option<usize> {
+        if self.is_selectable(n) {
+            return Some(n);
+        }
+        match (self.prev_selectable(n), self.next_selectable(n)) {
+            (Some(p), Some(q)) if q - n < n - p => Some(q),
+            (p, q) => p.or(q),
+        }
+    }
+
+    /// Advance the animation of [`Indicator::Spinner`](super::Indicator::Spinner)s by a frame.
+    /// Call this on a timer, e.g. each time the application redraws.
+    pub fn tick(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+    }
+
+    /// The number of times [`ListState::tick`] has been called.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// The line of the selected item that has the line cursor. This is 0 until the cursor is
+    /// moved with [`ListState::next_line`] or [`ListState::prev_line`], and goes back to 0 once
+    /// another item is rendered as selected.
+    pub fn selected_line(&self) -> usize {
+        match self.line_cursor {
+            (item, line) if Some(item) == self.selected => line,
+            _ => 0,
+        }
+    }
+
+    /// Move the line cursor to the next line of the selected item, or the first line of the next
+    /// item after the last line. The number of lines in the item is taken from the last render.
+    pub fn next_line(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let line = self.selected_line();
+        if line + 1 < self.selected_lines {
+            self.line_cursor = (selected, line + 1);
+        } else if let Some(n) = self.next_selectable(selected) {
+            self.selected = Some(n);
+            self.line_cursor = (n, 0);
+        }
+    }
+
+    /// Move the line cursor to the previous line of the selected item, or the last line of the
+    /// previous item from the first line.
+    pub fn prev_line(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let line = self.selected_line();
+        if line > 0 {
+            self.line_cursor = (selected, line - 1);
+        } else if let Some(n) = self.prev_selectable(selected) {
+            self.selected = Some(n);
+            let last = self.layout.item_lines(n).map_or(usize::MAX, |l| l - 1);
+            self.line_cursor = (n, last);
+        }
+    }
+
+    /// Record the number of lines of the selected item, and keep the line cursor within them.
+    pub(super) fn set_selected_lines(&mut self, lines: usize) {
+        self.selected_lines = lines;
+        if let Some(selected) = self.selected {
+            let line = min(self.selected_line(), lines.saturating_sub(1));
+            self.line_cursor = (selected, line);
+        }
+    }
+
+    /// Is the selection following the end of the list, as of the last render? See
+    /// [`WindowType::Follow`](super::WindowType::Follow).
+    pub fn is_following(&self) -> bool {
+        self.following
+    }
+
+    /// The number of items added to the list since it stopped following the end. See
+    /// [`WindowType::Follow`](super::WindowType::Follow).
+    pub fn new_items(&self) -> usize {
+        self.new_items
+    }
+
+    /// Record whether the list is following its end after a render. A list that follows does so
+    /// while the last item is selected and the window isn't scrolled away from it.
+    pub(super) fn set_following(&mut self, follow: bool) {
+        let last = self.size.checked_sub(1);
+        self.following = follow && self.selected == last && self.pin.is_none();
+        if self.following {
+            self.new_items = 0;
+        }
+    }
+
+    /// Remove `n` items from the front of the list, e.g. when the oldest items of a log are
+    /// dropped. The selection, marks and window stay on the same items, as far as those items are
+    /// still in the list. See [`ItemRing`](super::ItemRing).
+    pub fn remove_front(&mut self, n: usize) {
+        let shift = |i: usize| i.saturating_sub(n);
+        self.selected = self.selected.map(shift);
+        self.marked = self
+            .marked
+            .iter()
+            .filter(|&&i| i >= n)
+            .map(|&i| i - n)
+            .collect();
+        self.anchor = self.anchor.filter(|&a| a >= n).map(shift);
+        self.pin = self.pin.map(shift);
+        if let Some((index, _)) = self.scroll_request.as_mut() {
+            *index = shift(*index);
+        }
+        self.window_anchor.0 = shift(self.window_anchor.0);
+        self.line_cursor = match self.line_cursor {
+            (item, line) if item >= n => (item - n, line),
+            _ => (0, 0),
+        };
+        if let Some(keys) = self.keys.as_mut() {
+            keys.index = shift(keys.index);
+        }
+        if let Some(m) = self.matches.as_mut() {
+            m.retain(|&i| i >= n);
+            m.iter_mut().for_each(|i| *i -= n);
+        }
+        self.headers.retain(|&i| i >= n);
+        self.headers.iter_mut().for_each(|i| *i -= n);
+        self.hints.clear();
+        self.size = self.size.saturating_sub(n);
+        if self.size == 0 {
+            self.selected = None;
+        }
+    }
+
+    /// The key of the selected item, if it has one (see [`ListItem::key`](super::ListItem::key)).
+    /// This is the key as of the last render, or as given to [`ListState::select_key`].
+    pub fn selected_key(&self) -> Option<&ItemKey> {
+        self.keys
+            .as_ref()
+            .filter(|_| self.tracks_key())
+            .map(|k| &k.selected)
+    }
+
+    /// Select the item with `key` during the next render, e.g. to restore a selection that was
+    /// saved with [`ListState::selected_key`]. If there is no such item, the selection doesn't
+    /// move.
+    pub fn select_key<K: Into<ItemKey>>(&mut self, key: K) {
+        self.keys = Some(TrackedKeys {
+            index: self.selected.unwrap_or(0),
+            selected: key.into(),
+            after: None,
+            before: None,
+            anchor: None,
+        });
+    }
+
+    /// Is the selection where it was when the keys were recorded?
+    fn tracks_key(&self) -> bool {
+        self.keys
+            .as_ref()
+            .is_some_and(|k| Some(k.index) == self.selected)
+    }
+
+    /// Move the selection and the window to where their keys are now, with `find` giving the
+    /// index of the item with a key, given the index it was last seen near. The selection is left
+    /// alone if it was moved since the last render, or while following the end of the list.
+    /// Returns where the window should start if its first item moved.
+    pub(super) fn find_keys<F>(&mut self, find: F) -> Option<(usize, usize)>
+    where
+        F: Fn(&ItemKey, usize) -> Option<usize>,
+    {
+        let keys = self.keys.clone()?;
+        let near = |key: &ItemKey| find(key, keys.index);
+        if self.tracks_key() && !self.following {
+            let found = near(&keys.selected)
+                .or_else(|| keys.after.as_ref().and_then(near))
+                .or_else(|| keys.before.as_ref().and_then(near));
+            if let Some(n) = found {
+                self.select(n);
+            }
+        }
+        let anchor = keys
+            .anchor
+            .as_ref()
+            .and_then(|key| find(key, self.window_anchor.0));
+        match anchor {
+            Some(n) if n != self.window_anchor.0 => {
+                self.window_anchor.0 = n;
+                Some(self.window_anchor)
+            }
+            _ => None,
+        }
+    }
+
+    /// Record the keys around the selection and of the first item in the window. `keys` are the
+    /// keys of the items seen by the render, in order.
+    pub(super) fn set_keys(&mut self, keys: &[(usize, ItemKey)]) {
+        let position = |n: usize| keys.binary_search_by_key(&n, |(i, _)| *i).ok();
+        let key = |p: usize| keys.get(p).map(|(_, k)| k.clone());
+        self.keys = self.selected.and_then(|selected| {
+            let p = position(selected)?;
+            Some(TrackedKeys {
+                index: selected,
+                selected: keys[p].1.clone(),
+                after: key(p + 1),
+                before: p.checked_sub(1).and_then(key),
+                anchor: position(self.window_anchor.0).and_then(key),
+            })
+        });
+    }
+
+    /// Find what was drawn at the screen position `x`, `y` during the last render, e.g. to handle
+    /// a mouse click. Returns `None` outside of the list, or on blank rows.
+    pub fn item_at(&self, x: u16, y: u16) -> Option<Hit> {
+        match self.layout.hit(x, y)? {
+            Hit::Item { index, .. } if self.is_header(index) => Some(Hit::Header { index }),
+            hit => Some(hit),
+        }
+    }
+
     /// Select the next [ListItem](super::ListItem) without wrapping
     pub fn next(&mut self) {
-        self.selected = min(self.selected + 1, self.size - 1)
+        self.move_selection(Self::next_selectable);
     }
 
     /// Select the previous [ListItem](super::ListItem) without wrapping
     pub fn prev(&mut self) {
-        self.selected = self.selected.saturating_sub(1);
+        self.move_selection(Self::prev_selectable);
     }
 
     /// Select the next item in the list. If the current item is the last [ListItem`(super::ListItem), it will
     /// move the selection to the first [ListItem](super::ListItem)
     pub fn cycle_next(&mut self) {
-        self.selected = (self.selected + 1) % self.size;
+        self.move_selection(|s, selected| {
+            let first = || match s.is_selectable(0) {
+                true => Some(0),
+                false => s.next_selectable(0),
+            };
+            s.next_selectable(selected).or_else(first)
+        });
     }
 
     /// Select the previous item in the list. If the current item is the first [ListItem](super::ListItem), it will
     /// move the selection to the last [ListItem](super::ListItem)
     pub fn cycle_prev(&mut self) {
-        self.selected = (self.selected + self.size - 1) % self.size;
+        self.move_selection(|s, selected| {
+            let last = || s.prev_selectable(s.size);
+            s.prev_selectable(selected).or_else(last)
+        });
     }
 
     /// Specify which [ListItem](super::ListItem) is selected. If the selection is beyond the end of the list, the
-    /// last item will be selected.
+    /// last item will be selected. Nothing is selected in an empty list.
     pub fn select(&mut self, n: usize) {
-        self.selected = n;
-        if self.selected >= self.size {
-            self.selected = self.size.saturating_sub(1);
-        }
+        self.selected = self.size.checked_sub(1).map(|last| min(n, last));
     }
 
-    /// Get the index of the selected [ListItem](super::ListItem)
-    pub fn selected(&self) -> usize {
+    /// Get the index of the selected [ListItem](super::ListItem), or `None` if the list is empty
+    pub fn selected(&self) -> Option<usize> {
         self.selected
     }
 
     /// set the number of [ListItems](super::ListItem) in the list.
+    ///
+    /// If the list is following its end (see [`WindowType::Follow`](super::WindowType::Follow)),
+    /// the last item is selected. Otherwise any added items are counted by
+    /// [`ListState::new_items`].
+    ///
+    /// Resizing to 0 clears the selection, and resizing an empty list selects the first item.
     pub fn resize(&mut self, size: usize) {
-        if size == 0 {
-            panic!("ListState has invalid size: 0");
+        let tracked = self.tracks_key();
+        // Only follow if the selection hasn't moved off the end since the last render
+        if self.following && self.selected == self.size.checked_sub(1) {
+            self.selected = size.checked_sub(1);
+        } else {
+            self.new_items += size.saturating_sub(self.size);
         }
         self.size = size;
-        if self.selected >= self.size {
-            self.selected = self.size.saturating_sub(1);
+        self.select(self.selected.unwrap_or(0));
+        // A selection that had to move into the list still has its key
+        if let (true, Some(keys), Some(selected)) = (tracked, self.keys.as_mut(), self.selected) {
+            keys.index = selected;
+        }
+        self.marked.retain(|&i| i < size);
+        if matches!(self.anchor, Some(a) if a >= size) {
+            self.anchor = None;
+        }
+        if let Some(m) = self.matches.as_mut() {
+            m.retain(|&i| i < size);
         }
+        self.headers.retain(|&i| i < size);
+    }
+
+    /// Filter the list with `query`. The matching items are found during the next render of a
+    /// [`StyledList`](super::StyledList) with a [`Filter`](super::Filter). If the selected item
+    /// doesn't match, the best matching item is selected.
+    pub fn set_filter<S: Into<String>>(&mut self, query: S) {
+        self.filter = query.into();
+        if self.filter.is_empty() {
+            self.matches = None;
+        }
+    }
+
+    /// Remove the filter, so every item is displayed. The selected item stays the same.
+    pub fn clear_filter(&mut self) {
+        self.set_filter("");
+    }
+
+    /// The filter query
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Record the indices of the items matching the filter, and make sure a matching item is
+    /// selected, falling back to `best`.
+    pub(super) fn set_matches(&mut self, matches: Option<Vec<usize>>, best: Option<usize>) {
+        self.matches = matches;
+        if self.selected.is_some_and(|s| !self.is_selectable(s)) && best.is_some() {
+            self.selected = best;
+        }
+    }
+
+    /// Record the section headers seen while rendering, and the selection after moving it off
+    /// any header. If the selection was left on the last header, it moves back to the item
+    /// before it.
+    pub(super) fn set_headers(&mut self, headers: Vec<usize>, selected: Option<usize>) {
+        self.headers = headers;
+        self.selected = selected;
+        if self.selected.is_some_and(|s| self.is_header(s)) {
+            self.move_selection(Self::prev_selectable);
+        }
+        self.marked
+            .retain(|&i| self.headers.binary_search(&i).is_err());
+    }
+
+    /// The number of items matching the filter as of the last render. This is the size of the
+    /// list if it isn't filtered.
+    pub fn filtered_len(&self) -> usize {
+        self.matches.as_ref().map_or(self.size, Vec::len)
+    }
+
+    /// The position of the selected item among the items matching the filter.
+    pub fn filtered_index(&self) -> Option<usize> {
+        let selected = self.selected?;
+        match &self.matches {
+            Some(m) => m.binary_search(&selected).ok(),
+            None => Some(selected),
+        }
+    }
+
+    /// The position of the item at `n` among the items matching the filter, or the position it
+    /// would have if it matched.
+    pub(super) fn matched_position(&self, n: usize) -> usize {
+        match &self.matches {
+            Some(m) => m.partition_point(|&i| i < n),
+            None => n,
+        }
+    }
+
+    /// Select the item with the hint key `hint`, as drawn by the last render of a list with
+    /// [`Numbering::Hint`](super::Numbering::Hint). Returns whether there was such an item.
+    pub fn select_hint(&mut self, hint: &str) -> bool {
+        let count = self.hints.len();
+        let found = (0..count).find(|&n| gutter::hint_label(n, count) == hint);
+        if let Some(n) = found {
+            self.selected = Some(self.hints[n]);
+        }
+        found.is_some()
+    }
+
+    /// The index in the full list of the `n`th item matching the filter.
+    pub fn unfiltered_index(&self, n: usize) -> Option<usize> {
+        match &self.matches {
+            Some(m) => m.get(n).copied(),
+            None => (n < self.size).then_some(n),
+        }
+    }
+
+    /// The number of columns the content of the list is scrolled to the right.
+    pub fn h_offset(&self) -> usize {
+        self.h_offset
+    }
+
+    /// Scroll the content `n` columns left, towards the start of the lines.
+    pub fn scroll_left(&mut self, n: usize) {
+        self.h_request = None;
+        self.h_offset = self.h_offset.saturating_sub(n);
+    }
+
+    /// Scroll the content `n` columns right, towards the end of the lines. This will not scroll
+    /// past the end of the widest line displayed in the last render.
+    pub fn scroll_right(&mut self, n: usize) {
+        self.h_request = None;
+        self.h_offset += n;
+        if let Some(limit) = self.h_limit {
+            self.h_offset = min(self.h_offset, limit);
+        }
+    }
+
+    /// Scroll back to the start of the lines.
+    pub fn scroll_line_start(&mut self) {
+        self.h_request = None;
+        self.h_offset = 0;
+    }
+
+    /// Scroll so the end of the widest displayed line is visible. This is applied during the next
+    /// render.
+    pub fn scroll_line_end(&mut self) {
+        self.h_request = Some(HScroll::LineEnd);
+    }
+
+    /// Scroll so the end of the selected item's widest line is visible. This is applied during the
+    /// next render.
+    pub fn scroll_selected_tail(&mut self) {
+        self.h_request = Some(HScroll::SelectedTail);
+    }
+
+    /// Apply any pending horizontal scroll request, and keep the offset within the content.
+    /// `widest` is the width of the widest displayed line, `selected_widest` is the same for the
+    /// selected item and `view_width` is the number of columns available for the content.
+    pub(super) fn set_h_bounds(
+        &mut self,
+        widest: usize,
+        selected_widest: usize,
+        view_width: usize,
+    ) {
+        let limit = widest.saturating_sub(view_width);
+        match self.h_request.take() {
+            Some(HScroll::LineEnd) => self.h_offset = limit,
+            Some(HScroll::SelectedTail) => {
+                self.h_offset = selected_widest.saturating_sub(view_width)
+            }
+            None => {}
+        }
+        self.h_offset = min(self.h_offset, limit);
+        self.h_limit = Some(limit);
+    }
+
+    /// Scroll so the first line of the [ListItem](super::ListItem) at `index` is on the row given
+    /// by `align`, or as close as the ends of the list allow. This is applied during the next
+    /// render.
+    ///
+    /// The selection doesn't move, and the window stays where it is put even if the selection
+    /// is outside of it, until the selection changes.
+    pub fn scroll_to(&mut self, index: usize, align: Align) {
+        self.scroll_request = Some((index, align));
+        self.pin = self.selected;
+    }
+
+    /// Select the [ListItem](super::ListItem) at `index`, and scroll it to the row given by
+    /// `align` (see [`ListState::scroll_to`]).
+    pub fn select_and_scroll_to(&mut self, index: usize, align: Align) {
+        self.select(index);
+        if let Some(selected) = self.selected {
+            self.scroll_to(selected, align);
+        }
+    }
+
+    /// Scroll the window `n` lines down, towards the end of the list, without moving the
+    /// selection. This is applied during the next render, and won't scroll past the end of the
+    /// list.
+    ///
+    /// The selection can be scrolled out of the window. Changing the selection brings it back
+    /// into view, as the window type would normally place it.
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll_lines = self.scroll_lines.saturating_add_unsigned(n);
+        self.pin = self.selected;
+    }
+
+    /// Scroll the window `n` lines up, towards the start of the list, without moving the
+    /// selection. See [`ListState::scroll_down`].
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll_lines = self.scroll_lines.saturating_sub_unsigned(n);
+        self.pin = self.selected;
+    }
+
+    /// Is the window pinned in place? Changing the selection releases it.
+    pub(super) fn is_pinned(&self) -> bool {
+        self.pin.is_some() && self.pin == self.selected
+    }
+
+    /// Drop the pin, and any lines scrolled with it, once the selection has moved.
+    pub(super) fn release_pin(&mut self) {
+        if !self.is_pinned() {
+            self.pin = None;
+            self.scroll_lines = 0;
+        }
+    }
+
+    /// Move the window by any lines scrolled since the last render.
+    pub(super) fn apply_scroll_lines(&mut self) {
+        let n = std::mem::take(&mut self.scroll_lines);
+        self.window_first = self.window_first.saturating_add_signed(n);
+    }
+
+    /// Take the pending [`ListState::scroll_to`] request, and the row it should be on in a window
+    /// `height` rows tall.
+    pub(super) fn take_scroll_request(&mut self, height: usize) -> Option<(usize, usize)> {
+        self.scroll_request
+            .take()
+            .map(|(index, align)| (index, align.row(height)))
+    }
+
+    /// Is the [ListItem](super::ListItem) at index `n` marked?
+    pub fn is_marked(&self, n: usize) -> bool {
+        self.marked.contains(&n)
+    }
+
+    /// Iterate over the indices of the marked [ListItems](super::ListItem) in ascending order.
+    pub fn marked(&self) -> impl Iterator<Item = usize> + '_ {
+        self.marked.iter().copied()
+    }
+
+    /// Toggle the mark on the selected [ListItem](super::ListItem). The selected item also becomes
+    /// the anchor for [`ListState::extend_to`].
+    pub fn toggle_mark(&mut self) {
+        let Some(selected) = self.selected.filter(|&s| !self.is_header(s)) else {
+            return;
+        };
+        if !self.marked.remove(&selected) {
+            self.marked.insert(selected);
+        }
+        self.anchor = Some(selected);
+    }
+
+    /// Select the [ListItem](super::ListItem) `n`, and mark every item between the anchor and `n`
+    /// (inclusive), replacing any other marks. This works like shift-click in most GUI lists: if no
+    /// anchor has been set, the currently selected item becomes the anchor.
+    ///
+    /// If `n` can't be selected, the range ends at the nearest item that can. Headers and items
+    /// hidden by the filter are never marked.
+    pub fn extend_to(&mut self, n: usize) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let Some(n) = self.nearest_selectable(min(n, self.size - 1)) else {
+            return;
+        };
+        let anchor = *self.anchor.get_or_insert(selected);
+        self.selected = Some(n);
+        let (lo, hi) = if anchor <= n {
+            (anchor, n)
+        } else {
+            (n, anchor)
+        };
+        self.marked = (lo..=hi).filter(|&i| self.is_selectable(i)).collect();
+    }
+
+    /// Extend the marked range to the next [ListItem](super::ListItem) without wrapping
+    pub fn extend_next(&mut self) {
+        if let Some(s) = self.selected {
+            self.extend_to(self.next_selectable(s).unwrap_or(s));
+        }
+    }
+
+    /// Extend the marked range to the previous [ListItem](super::ListItem) without wrapping
+    pub fn extend_prev(&mut self) {
+        if let Some(s) = self.selected {
+            self.extend_to(self.prev_selectable(s).unwrap_or(s));
+        }
+    }
+
+    /// Mark every [ListItem](super::ListItem) in the list.
+    pub fn mark_all(&mut self) {
+        self.marked = (0..self.size).filter(|&i| !self.is_header(i)).collect();
+    }
+
+    /// Mark every unmarked [ListItem](super::ListItem), and unmark every marked one.
+    pub fn invert_marks(&mut self) {
+        self.marked = (0..self.size)
+            .filter(|i| !self.marked.contains(i) && !self.is_header(*i))
+            .collect();
+    }
+
+    /// Remove all marks, and forget the anchor.
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.anchor = None;
     }
 }
 
+/// Deserialize the selection from an index, as it was saved before empty lists were allowed, or
+/// from an optional index.
+#[cfg(feature = "serde")]
+fn deserialize_selected<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Selected {
+        Index(usize),
+        Optional(Option<usize>),
+    }
+    let selected = <Selected as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(match selected {
+        Selected::Index(i) => Some(i),
+        Selected::Optional(o) => o,
+    })
+}
+
 impl Default for ListState {
     fn default() -> Self {
         ListState::new(1)
@@ -95,60 +1062,216 @@ mod tests {
     #[test]
     fn cycles() {
         let mut s = ListState::new(3);
-        assert_eq!(s.selected(), 0);
+        assert_eq!(s.selected(), Some(0));
         s.cycle_next();
-        assert_eq!(s.selected(), 1);
+        assert_eq!(s.selected(), Some(1));
         s.cycle_next();
-        assert_eq!(s.selected(), 2);
+        assert_eq!(s.selected(), Some(2));
         s.cycle_next();
-        assert_eq!(s.selected(), 0);
+        assert_eq!(s.selected(), Some(0));
 
         s.cycle_prev();
-        assert_eq!(s.selected(), 2);
+        assert_eq!(s.selected(), Some(2));
         s.cycle_prev();
-        assert_eq!(s.selected(), 1);
+        assert_eq!(s.selected(), Some(1));
         s.cycle_prev();
-        assert_eq!(s.selected(), 0);
+        assert_eq!(s.selected(), Some(0));
+    }
+
+    #[test]
+    fn skips_headers() {
+        let mut s = ListState::new(6);
+        s.set_headers(vec![0, 3], Some(1));
+        s.next();
+        assert_eq!(s.selected(), Some(2));
+        s.next();
+        assert_eq!(s.selected(), Some(4));
+        s.prev();
+        assert_eq!(s.selected(), Some(2));
+
+        s.select(5);
+        s.cycle_next();
+        assert_eq!(s.selected(), Some(1));
+        s.cycle_prev();
+        assert_eq!(s.selected(), Some(5));
+
+        s.mark_all();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![1, 2, 4, 5]);
     }
 
     #[test]
     fn movement() {
         let mut s = ListState::new(3);
-        assert_eq!(s.selected(), 0);
+        assert_eq!(s.selected(), Some(0));
         s.prev();
-        assert_eq!(s.selected(), 0);
+        assert_eq!(s.selected(), Some(0));
         s.next();
-        assert_eq!(s.selected(), 1);
+        assert_eq!(s.selected(), Some(1));
         s.next();
-        assert_eq!(s.selected(), 2);
+        assert_eq!(s.selected(), Some(2));
         s.next();
-        assert_eq!(s.selected(), 2);
+        assert_eq!(s.selected(), Some(2));
     }
 
     #[test]
     fn resize() {
         let mut s = ListState::new(3);
         s.select(2);
-        assert_eq!(s.selected(), 2);
+        assert_eq!(s.selected(), Some(2));
         s.resize(2);
-        assert_eq!(s.selected(), 1);
+        assert_eq!(s.selected(), Some(1));
         s.resize(4);
-        assert_eq!(s.selected(), 1);
+        assert_eq!(s.selected(), Some(1));
         s.cycle_prev();
         s.cycle_prev();
-        assert_eq!(s.selected(), 3);
+        assert_eq!(s.selected(), Some(3));
+    }
+
+    #[test]
+    fn marks() {
+        let mut s = ListState::new(5);
+        s.toggle_mark();
+        s.next();
+        s.next();
+        s.toggle_mark();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(s.selected(), Some(2));
+
+        s.toggle_mark();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![0]);
+
+        s.invert_marks();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        s.resize(3);
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![1, 2]);
+
+        s.clear_marks();
+        assert_eq!(s.marked().count(), 0);
+        s.mark_all();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut s = ListState::new(6);
+        s.select(2);
+        s.extend_next();
+        s.extend_next();
+        assert_eq!(s.selected(), Some(4));
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        // moving back past the anchor flips the range
+        s.extend_to(0);
+        assert_eq!(s.selected(), Some(0));
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        // toggling sets a new anchor
+        s.select(5);
+        s.toggle_mark();
+        s.extend_prev();
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![4, 5]);
+
+        // a range can't end on an item hidden by the filter
+        s.matches = Some(vec![0, 1, 2, 4]);
+        s.extend_to(3);
+        assert_eq!(s.selected(), Some(2));
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![2, 4]);
+        s.matches = None;
+
+        // a range can't end on a header
+        s.headers = vec![0, 3];
+        s.extend_to(3);
+        assert_eq!(s.selected(), Some(2));
+        assert_eq!(s.marked().collect::<Vec<_>>(), vec![2, 4, 5]);
     }
 
     #[test]
-    #[should_panic]
-    fn zero_size_create() {
-        let _ = ListState::new(0);
+    fn h_scroll() {
+        let mut s = ListState::new(3);
+        s.scroll_right(5);
+        s.scroll_left(2);
+        assert_eq!(s.h_offset(), 3);
+
+        // bounded by the render
+        s.set_h_bounds(10, 6, 8);
+        assert_eq!(s.h_offset(), 2);
+        s.scroll_right(5);
+        assert_eq!(s.h_offset(), 2);
+
+        s.scroll_line_start();
+        assert_eq!(s.h_offset(), 0);
+        s.scroll_selected_tail();
+        s.set_h_bounds(20, 12, 8);
+        assert_eq!(s.h_offset(), 4);
+        s.scroll_line_end();
+        s.set_h_bounds(20, 12, 8);
+        assert_eq!(s.h_offset(), 12);
     }
 
     #[test]
-    #[should_panic]
-    fn zero_size_resize() {
-        let mut s = ListState::new(1);
+    fn filtered_movement() {
+        let mut s = ListState::new(6);
+        s.select(3);
+        s.set_filter("x");
+        s.set_matches(Some(vec![1, 4, 5]), Some(4));
+        assert_eq!(s.selected(), Some(4));
+        assert_eq!(s.filtered_index(), Some(1));
+        assert_eq!(s.filtered_len(), 3);
+        assert_eq!(s.unfiltered_index(2), Some(5));
+
+        s.next();
+        assert_eq!(s.selected(), Some(5));
+        s.next();
+        assert_eq!(s.selected(), Some(5));
+        s.cycle_next();
+        assert_eq!(s.selected(), Some(1));
+        s.prev();
+        assert_eq!(s.selected(), Some(1));
+        s.cycle_prev();
+        assert_eq!(s.selected(), Some(5));
+
+        // clearing the filter keeps the item selected
+        s.prev();
+        s.clear_filter();
+        assert_eq!(s.selected(), Some(4));
+        s.next();
+        assert_eq!(s.selected(), Some(5));
+    }
+
+    #[test]
+    fn empty() {
+        let mut s = ListState::new(0);
+        assert_eq!(s.selected(), None);
+        s.next();
+        s.cycle_prev();
+        s.select_last();
+        s.select(3);
+        s.toggle_mark();
+        s.extend_next();
+        s.select_down(GridEdge::Wrap);
+        s.next_line();
+        assert_eq!(s.selected(), None);
+        assert_eq!(s.filtered_index(), None);
+        assert_eq!(s.marked().count(), 0);
+
+        s.resize(3);
+        assert_eq!(s.selected(), Some(0));
+        s.select(2);
+        s.toggle_mark();
         s.resize(0);
+        assert_eq!(s.selected(), None);
+        assert_eq!(s.marked().count(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_selection() {
+        use serde::de::{value::Error, IntoDeserializer};
+
+        let index = deserialize_selected(3usize.into_deserializer());
+        assert_eq!(index, Ok::<_, Error>(Some(3)));
+        let none = deserialize_selected(().into_deserializer());
+        assert_eq!(none, Ok::<_, Error>(None));
     }
 }
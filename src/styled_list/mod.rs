@@ -7,7 +7,7 @@
 //
 // The transforms go like this:
 //
-// ListItem -> ToLines -> DisplayLine
+// ListItem -> (wrap) -> ToLines -> DisplayLine
 //
 // ToLines is an internal iterator over the text of of the ListItem's line of text, turing them
 // into DisplayLines
@@ -17,30 +17,56 @@
 // The rendering pipeline goes like this:
 //
 // items
+// -> If filtering, match each item against the query, dropping or dimming the non-matches
 // -> Apply selection styling based on state
+// -> If wrapping is enabled, split the lines of each item to fit the area
 // -> flatten iter of ToLines to iter of DisplayLine, insert any DisplayLines for the ItemDisplay
 //    style
 // -> Filter the DiplayLines iterator to only the lines to draw via the window iterator.
+// -> Cut each remaining line down to the horizontally scrolled columns while drawing.
 //
 // The window iterators process in a single pass so this pipeline is at worst O(n) (althougth if
 // window fills up before finishing the display, it will stop iteration before that.
+mod filter;
+mod grid;
+mod gutter;
+#[cfg(feature = "input")]
+pub mod input;
+mod layout;
 mod line_iters;
 mod list_item;
 mod list_state;
+mod scrollbar;
 mod separator;
+mod source;
+mod spans;
+mod style_rule;
+mod type_ahead;
 mod window_type;
 
+use std::{cmp::min, sync::Arc};
+
+use bounded_vec_deque::BoundedVecDeque;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
-    text::Spans,
+    style::{Modifier, Style},
+    text::{Span, Spans, Text},
     widgets::{Block, StatefulWidget, Widget},
 };
 
-pub use list_item::{Indicator, LineIndicators, ListItem};
-pub use list_state::ListState;
+pub use filter::{Filter, FilterMode};
+pub use grid::Columns;
+pub use gutter::Numbering;
+pub use layout::{Hit, HitColumn};
+pub use list_item::{Indicator, IndicatorFn, ItemKey, LineIndicators, ListItem};
+pub use list_state::{Align, GridEdge, ListState};
+pub use scrollbar::{Scrollbar, ScrollbarPlacement, ScrollbarSide};
 use separator::Separator;
+pub use separator::{Blank, HalfBlock, LabelledRule, Rule, SeparatorStyle};
+pub use source::{ItemRing, ItemSource, Source};
+pub use style_rule::StyleRule;
+pub use type_ahead::{Clock, SystemClock, TypeAhead};
 
 /// A rendered line of text in the list widget. Multiple DisplayLines can be created from a single
 /// [`ListItem`]. The window operates on an iterable of [`DiplayLine`]s
@@ -49,19 +75,64 @@ struct DisplayLine<'a> {
     pub(super) style: Style,
     pub(super) line: Spans<'a>,
     pub(super) must_display: bool,
+    pub(super) kind: LineKind,
     pub(super) left_indicator: Spans<'a>,
     pub(super) right_indicator: Spans<'a>,
+    /// Is this a line of a section header?
+    pub(super) header: bool,
+}
+
+/// What a [`DisplayLine`] was generated from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LineKind {
+    /// Line `line` of the text from the [`ListItem`] at `index`. `last` is set on the item's
+    /// last line.
+    Item {
+        index: usize,
+        line: usize,
+        last: bool,
+    },
+    /// A line generated by [`ItemDisplay::SeparatedBy`], between the items at `above` and `below`
+    Separator {
+        above: Option<usize>,
+        below: Option<usize>,
+    },
+    /// Blank space added by the window
+    Filler,
 }
 
 /// Control how lines are rendered
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum ItemDisplay {
     /// Basic `ItemDisplay` simply renders each text line in the [`ListItem`] iterator into a
     /// display line.
     Basic,
     /// Separated `ItemDisplay` places a separator between each [`ListItem`] (including
-    /// endcaps so items A, B, C will be rendered as `S A1 A2 S B1 S C1 S`)
+    /// endcaps so items A, B, C will be rendered as `S A1 A2 S B1 S C1 S`). The separators are
+    /// drawn with [`HalfBlock`].
     Separated,
+    /// Like [`ItemDisplay::Separated`], with separators drawn by the given [`SeparatorStyle`],
+    /// e.g. `ItemDisplay::separated_by(Rule::DASHED)`.
+    SeparatedBy(Arc<dyn SeparatorStyle>),
+    /// Flow the items into a grid, left to right and then top to bottom. Each cell shows all the
+    /// lines of its item, with its own indicators, and each row is as tall as its tallest item.
+    /// Use the grid navigation of the [`ListState`] (e.g. [`ListState::select_down`]) to move
+    /// between rows.
+    ///
+    /// The window scrolls by whole rows to keep the selected row visible, so the
+    /// [`WindowType`] isn't used. Filtering, section headers and horizontal scrolling aren't
+    /// supported in a grid.
+    Grid(Columns),
+}
+
+/// Control how lines wider than the list are wrapped. See [`StyledList::wrap`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Break lines at any character.
+    Char,
+    /// Break lines between words where possible. Words wider than the list are broken at any
+    /// character.
+    Word,
 }
 
 /// Control how the window places itself with respect to the rendered lines, i.e. control the list
@@ -75,13 +146,27 @@ pub enum WindowType {
     /// Display the rendered lines so that the selected [`ListItem`] always displays in the same
     /// place on the screen. Effectively this always "moves the list" around the selection.
     Fixed(usize),
+    /// Like [`WindowType::SelectionScroll`], but keep at least this many lines visible above and
+    /// below the selected [`ListItem`] (like vim's `scrolloff`). The margins shrink if the window
+    /// is too small to fit them around the selection, and at the ends of the list.
+    ScrollOff(usize),
+    /// Keep the selected [`ListItem`] in the middle of the window, except near the ends of the
+    /// list, where the window stops at the first or last line.
+    Centered,
+    /// Like [`WindowType::SelectionScroll`], but while the last item is selected the selection
+    /// follows the end of the list as items are added (see [`ListState::resize`]), e.g. for a
+    /// log viewer. Moving the selection up (or scrolling the window with
+    /// [`ListState::scroll_up`]) stops following, and the number of items added since is shown
+    /// on the last row (see [`StyledList::new_lines_style`]). Selecting the last item again
+    /// resumes following.
+    Follow,
 }
 
 /// A general purpose List widget that has several modes of display
-pub struct StyledList<'a, I>
-where
-    I: IntoIterator<Item = ListItem<'a>>,
-{
+///
+/// The items are usually anything that can be iterated for [`ListItem`]s. Very large lists can
+/// instead be built from an [`ItemSource`] with [`StyledList::from_source`].
+pub struct StyledList<'a, I> {
     block: Option<Block<'a>>,
     default_style: Style,
     selected_style: Style,
@@ -90,6 +175,18 @@ where
     show_right_indicator: bool,
     window_type: WindowType,
     item_display: ItemDisplay,
+    wrap: Option<WrapMode>,
+    overflow_markers: Option<(&'static str, &'static str)>,
+    scrollbar: Option<Scrollbar>,
+    filter: Option<Filter>,
+    header_style: Style,
+    sticky_headers: bool,
+    numbering: Option<Numbering>,
+    new_lines_style: Style,
+    bottom_up: bool,
+    style_rules: Vec<StyleRule>,
+    cursor_line_style: Option<Style>,
+    empty_text: Option<Text<'a>>,
     items: I,
 }
 
@@ -98,6 +195,25 @@ where
     I: IntoIterator<Item = ListItem<'a>>,
 {
     pub fn new(items: I) -> Self {
+        Self::with_items(items)
+    }
+}
+
+impl<'a, S> StyledList<'a, Source<S>>
+where
+    S: ItemSource<'a>,
+{
+    /// Create a list that takes items from `source` as needed, starting near the window.
+    ///
+    /// The [`ListState`] is resized to the length of the source on each render. Filtering has to
+    /// look at every item, so a filtered list is rendered from the first item.
+    pub fn from_source(source: S) -> Self {
+        Self::with_items(Source(source))
+    }
+}
+
+impl<'a, I> StyledList<'a, I> {
+    fn with_items(items: I) -> Self {
         Self {
             items,
             block: None,
@@ -108,8 +224,60 @@ where
             show_right_indicator: false,
             window_type: WindowType::SelectionScroll,
             item_display: ItemDisplay::Basic,
+            wrap: None,
+            overflow_markers: None,
+            scrollbar: None,
+            filter: None,
+            header_style: Style::default(),
+            sticky_headers: true,
+            numbering: None,
+            new_lines_style: Style::default().add_modifier(Modifier::REVERSED),
+            bottom_up: false,
+            style_rules: Vec::new(),
+            cursor_line_style: None,
+            empty_text: None,
         }
     }
+
+    /// Use `items` in place of the list's items, keeping the rest of the configuration.
+    #[cfg(feature = "tree")]
+    pub(crate) fn replace_items<J>(self, items: J) -> StyledList<'a, J> {
+        self.swap_items(items).0
+    }
+
+    /// Separate the items from the rest of the list, so the items can be rendered by the shared
+    /// pipeline.
+    fn take_items(self) -> (StyledList<'a, ()>, I) {
+        self.swap_items(())
+    }
+
+    fn swap_items<J>(self, items: J) -> (StyledList<'a, J>, I) {
+        let list = StyledList {
+            items,
+            block: self.block,
+            default_style: self.default_style,
+            selected_style: self.selected_style,
+            selected_indicator: self.selected_indicator,
+            show_left_indicator: self.show_left_indicator,
+            show_right_indicator: self.show_right_indicator,
+            window_type: self.window_type,
+            item_display: self.item_display,
+            wrap: self.wrap,
+            overflow_markers: self.overflow_markers,
+            scrollbar: self.scrollbar,
+            filter: self.filter,
+            header_style: self.header_style,
+            sticky_headers: self.sticky_headers,
+            numbering: self.numbering,
+            new_lines_style: self.new_lines_style,
+            bottom_up: self.bottom_up,
+            style_rules: self.style_rules,
+            cursor_line_style: self.cursor_line_style,
+            empty_text: self.empty_text,
+        };
+        (list, self.items)
+    }
+
     /// Wrap the list in a block (e.g. to set borders or a title).
     pub fn block(mut self, b: Block<'a>) -> Self {
         self.block = Some(b);
@@ -123,26 +291,30 @@ where
         self
     }
 
-    /// The style applied to lines of the selected item. If the this list uses [`ItemDisplay::Separated`]
-    /// the surrounding separators will also be highlighted using this style.
+    /// The style applied to lines of the selected item, and any items marked in the
+    /// [`ListState`]. If the this list uses [`ItemDisplay::Separated`] the surrounding separators
+    /// will also be highlighted using this style.
     pub fn selected_style(mut self, s: Style) -> Self {
         self.selected_style = s;
         self
     }
 
-    /// The indicators to use for the selected item
+    /// The indicators to use for the selected item and any marked items
     pub fn selected_indicator(mut self, indicator: LineIndicators) -> Self {
         self.selected_indicator = indicator;
         self
     }
 
-    /// Display the left indicator column - if not set the left indicator will not be displayed
+    /// Display the left indicator column - if not set the left indicator will not be displayed.
+    /// The column is as wide as the widest indicator drawn in it. Wrapped lines leave room for
+    /// the selected indicator.
     pub fn show_left_indicator(mut self) -> Self {
         self.show_left_indicator = true;
         self
     }
 
-    /// Display the right indicator column - if not set the right indicator will not be displayed
+    /// Display the right indicator column - if not set the right indicator will not be displayed.
+    /// See [`StyledList::show_left_indicator`].
     pub fn show_right_indicator(mut self) -> Self {
         self.show_right_indicator = true;
         self
@@ -159,6 +331,111 @@ where
         self.item_display = it;
         self
     }
+
+    /// Wrap lines that are wider than the list, instead of truncating them. Each wrapped row is
+    /// its own line of the [`ListItem`], so [`Indicator`]s refer to the wrapped rows.
+    pub fn wrap(mut self, mode: WrapMode) -> Self {
+        self.wrap = Some(mode);
+        self
+    }
+
+    /// Draw `left` at the start of a line when it is scrolled horizontally (see
+    /// [`ListState::scroll_right`]), and `right` at the end of a line that is too wide to be
+    /// displayed in full.
+    pub fn overflow_markers(mut self, left: &'static str, right: &'static str) -> Self {
+        self.overflow_markers = Some((left, right));
+        self
+    }
+
+    /// Draw a vertical scrollbar showing the position of the window in the list.
+    pub fn scrollbar(mut self, scrollbar: Scrollbar) -> Self {
+        self.scrollbar = Some(scrollbar);
+        self
+    }
+
+    /// Filter the items with the query set by [`ListState::set_filter`].
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// The style applied to section headers (see [`ListItem::header`]). The header's own style is
+    /// patched into this.
+    pub fn header_style(mut self, s: Style) -> Self {
+        self.header_style = s;
+        self
+    }
+
+    /// Number the items in a gutter to the left of the indicators. The numbers are right aligned
+    /// to the widest number the list can have, and drawn in the style of the item. Continuation
+    /// lines of multi-line items are left blank. The gutter isn't drawn in a grid.
+    pub fn numbering(mut self, numbering: Numbering) -> Self {
+        self.numbering = Some(numbering);
+        self
+    }
+
+    /// Draw the list from the bottom of the area up: the first item is on the bottom row, and
+    /// each item is drawn above the one before it, e.g. for a chat history with the newest
+    /// message first. The lines of each item are still drawn top to bottom. A list too short to
+    /// fill the area sits against the bottom edge.
+    ///
+    /// The window works the same way as in a top down list, turned upside down, and so do
+    /// the separators and the scrollbar. Headers aren't pinned and grids aren't flipped.
+    pub fn bottom_up(mut self) -> Self {
+        self.bottom_up = true;
+        self
+    }
+
+    /// Text drawn in the middle of the list when it has no items, e.g. "No matches". The text is
+    /// drawn in the default style, with the styles of its spans patched in.
+    pub fn empty_text<T: Into<Text<'a>>>(mut self, text: T) -> Self {
+        self.empty_text = Some(text.into());
+        self
+    }
+
+    /// The style patched into the line of the selected item that has the line cursor (see
+    /// [`ListState::next_line`]). If the selected item is taller than the window, the window
+    /// scrolls to keep the cursor line visible whether or not this is set.
+    pub fn cursor_line_style(mut self, s: Style) -> Self {
+        self.cursor_line_style = Some(s);
+        self
+    }
+
+    /// Style the items by their position, e.g. `StyleRule::Odd(style)` for zebra striping. The
+    /// rules are patched together in the order they are added, and patched into the default
+    /// style before the item's own style and the selected style.
+    pub fn style_rule(mut self, rule: StyleRule) -> Self {
+        self.style_rules.push(rule);
+        self
+    }
+
+    /// The style of the count of new items shown by [`WindowType::Follow`] when the list isn't
+    /// following the end. This is reversed by default.
+    pub fn new_lines_style(mut self, s: Style) -> Self {
+        self.new_lines_style = s;
+        self
+    }
+
+    /// Pin the header of the section at the top of the window to the first row, so it stays
+    /// visible while the items in the section scroll. This is on by default.
+    pub fn sticky_headers(mut self, sticky: bool) -> Self {
+        self.sticky_headers = sticky;
+        self
+    }
+}
+
+/// Where the items given to the render pipeline start, relative to the whole list.
+struct ItemsStart {
+    /// Lines from items before this are dropped before the window. Including an item before the
+    /// window gives the separators the right colors.
+    first_item: usize,
+    /// The number of display lines before `first_item`
+    base_line: usize,
+    /// The total number of display lines, if it is known without counting them
+    total_lines: Option<usize>,
+    /// Where the window should try to start, as an item and a line within it. Otherwise the
+    /// position in the [`ListState`] is used.
+    goal: Option<(usize, usize)>,
 }
 
 impl<'a, I> StatefulWidget for StyledList<'a, I>
@@ -168,9 +445,125 @@ where
     type State = ListState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let (list, items) = self.take_items();
+        let mut start = ItemsStart {
+            first_item: 0,
+            base_line: 0,
+            total_lines: None,
+            goal: None,
+        };
+        if state.keys.is_none() {
+            list.render_items(items.into_iter().enumerate(), start, area, buf, state);
+            return;
+        }
+
+        // Find the keyed items, which may have moved since the last render
+        let items: Vec<ListItem<'a>> = items.into_iter().collect();
+        start.goal = state.find_keys(|key, near| items.key_index(key, near));
+        list.render_items(items.into_iter().enumerate(), start, area, buf, state);
+    }
+}
+
+impl<'a, S> StatefulWidget for StyledList<'a, Source<S>>
+where
+    S: ItemSource<'a>,
+{
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let (list, Source(source)) = self.take_items();
+        let len = source.len();
+        state.resize(len);
+        if state.keys.is_some() {
+            state.find_keys(|key, near| source.key_index(key, near));
+        }
+
+        // A grid lays out every item, as does filtering
+        let grid = matches!(list.item_display, ItemDisplay::Grid(_));
+        if grid || (list.filter.is_some() && !state.filter.is_empty()) {
+            let items = (0..len).map(|i| (i, source.item(i)));
+            let start = ItemsStart {
+                first_item: 0,
+                base_line: 0,
+                total_lines: None,
+                goal: None,
+            };
+            list.render_items(items, start, area, buf, state);
+            return;
+        }
+
+        // Start from the first item the window could need, and let the window work relative
+        // to that.
+        let separated = list.item_display.is_separated();
+        let height = area.height as usize;
+        let pinned = state.is_pinned();
+        let (first_item, goal) = match state.scroll_request {
+            Some((index, _)) => (index.saturating_sub(height), None),
+            None if pinned => {
+                // Each item is at least a line, so this covers any lines scrolled up
+                let anchor = state.window_anchor;
+                let up = state.scroll_lines.min(0).unsigned_abs();
+                (anchor.0.saturating_sub(height + up), Some(anchor))
+            }
+            None => list.window_type.first_item(
+                state.selected.unwrap_or(0),
+                state.window_anchor,
+                height,
+                separated,
+            ),
+        };
+        let start = ItemsStart {
+            first_item,
+            base_line: source::lines_before(&source, first_item, separated),
+            total_lines: Some(
+                source::lines_before(&source, len, separated) + usize::from(separated),
+            ),
+            goal,
+        };
+        state.set_pos(0);
+
+        // The header of the section the window starts in is needed to pin it
+        let context = first_item.saturating_sub(1);
+        let header = if list.sticky_headers && len > 0 {
+            source.header_before(context).filter(|&h| h < context)
+        } else {
+            None
+        };
+        let items = header
+            .into_iter()
+            .chain(context..len)
+            .map(|i| (i, source.item(i)));
+        list.render_items(items, start, area, buf, state);
+    }
+}
+
+impl<'a> StyledList<'a, ()> {
+    /// The widths of the left and right indicator columns. A shown column is as wide as the
+    /// widest of the selected indicator and the indicator `widths`, and at least one column.
+    fn indicator_widths<W>(&self, widths: W) -> (u16, u16)
+    where
+        W: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut left = self.selected_indicator.left.width().max(1);
+        let mut right = self.selected_indicator.right.width().max(1);
+        for (l, r) in widths {
+            left = left.max(l);
+            right = right.max(r);
+        }
+        let shown = |show: bool, width: usize| if show { width as u16 } else { 0 };
+        (
+            shown(self.show_left_indicator, left),
+            shown(self.show_right_indicator, right),
+        )
+    }
+
+    /// Draw the block and the default style, returning the area of the scrollbar (if any) and the
+    /// area left for the items.
+    fn frame(&mut self, area: Rect, buf: &mut Buffer) -> (Option<Rect>, Rect) {
         // Block is used for borders and such
         // Draw that first, and use the blank area inside the block for our own purposes
-        let area = match self.block {
+        let outer = area;
+        let area = match self.block.take() {
             None => area,
             Some(b) => {
                 let inner = b.inner(area);
@@ -182,35 +575,319 @@ where
         // set style for whole area
         buf.set_style(area, self.default_style);
 
-        let sep = Separator::new(area.width as usize, self.default_style);
+        // The scrollbar takes its column before the items are laid out
+        match self.scrollbar {
+            Some(sb) => {
+                let (column, rest) = sb.layout(outer, area);
+                (Some(column), rest)
+            }
+            None => (None, area),
+        }
+    }
+
+    /// Draw a list with no items: just the frame, and the empty text centered in it.
+    fn render_empty(mut self, area: Rect, buf: &mut Buffer, state: &mut ListState) {
+        let (_, area) = self.frame(area, buf);
+        if let Some(text) = self.empty_text.take() {
+            let top = area.height.saturating_sub(text.height() as u16) / 2;
+            for (y, line) in (area.y + top..area.bottom()).zip(text.lines) {
+                let width = min(line.width() as u16, area.width);
+                let x = area.x + (area.width - width) / 2;
+                buf.set_spans(x, y, &line, width);
+            }
+        }
+
+        state.set_headers(Vec::new(), None);
+        state.hints.clear();
+        state.set_pos(0);
+        state.set_window(0, Some(0));
+        state.layout = layout::ListLayout {
+            area,
+            ..Default::default()
+        };
+    }
+
+    /// The rendering pipeline shared by all lists. `items` are the [`ListItem`]s paired with their
+    /// index in the list.
+    fn render_items<I>(
+        mut self,
+        items: I,
+        start: ItemsStart,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut ListState,
+    ) where
+        I: Iterator<Item = (usize, ListItem<'a>)>,
+    {
+        state.release_pin();
+        if state.size == 0 {
+            self.render_empty(area, buf, state);
+            return;
+        }
+        if let ItemDisplay::Grid(columns) = self.item_display {
+            let items = items.map(|(_, it)| it);
+            self.render_grid(items, columns, area, buf, state);
+            return;
+        }
+
+        let (scrollbar_area, area) = self.frame(area, buf);
+
+        // the width left for item text once the indicator columns are drawn. Wrapping needs it
+        // before the lines are laid out, so then the columns are sized from the indicators of
+        // every item rather than those in the window.
+        let gutter = self
+            .numbering
+            .map_or(0, |n| n.width(state, area.height as usize));
+        let (items, wrap_widths): (Box<dyn Iterator<Item = (usize, ListItem<'a>)>>, _) =
+            if self.wrap.is_some() {
+                let items: Vec<_> = items.collect();
+                let widths = items.iter().map(|(_, it)| it.indicators.widths());
+                let widths = self.indicator_widths(widths);
+                (Box::new(items.into_iter()), Some(widths))
+            } else {
+                (Box::new(items), None)
+            };
+        let (left_width, right_width) = wrap_widths.unwrap_or_default();
+        let text_width = area.width.saturating_sub(gutter + left_width + right_width) as usize;
+
+        // Filter the items. The selection can only be styled once it is known which items
+        // match, so a filtered list is matched in full before moving on.
+        let items: Box<dyn Iterator<Item = (usize, ListItem<'a>, bool)>> = match self.filter {
+            Some(f) if !state.filter.is_empty() => {
+                let (items, matches, best) = f.apply(items, &state.filter);
+                state.set_matches(Some(matches), best);
+                Box::new(items.into_iter())
+            }
+            _ => {
+                state.set_matches(None, None);
+                Box::new(items.map(|(i, it)| (i, it, true)))
+            }
+        };
 
         // Start the pipeline: appy indicators and patch in appropriate stylings.
-        // Then convert to a ToLines.
-        let selected = state.selected;
-        let iter = self.items.into_iter().enumerate().map(|(i, mut it)| {
-            if i == selected {
-                it = it.indicators(self.selected_indicator);
-                it.style = self
-                    .default_style
-                    .patch(it.style.patch(self.selected_style));
+        // Then convert to a ToLines. Marked items are styled as selected, but only the
+        // selected item (the cursor) is tracked by the window. Headers can't be selected, so if
+        // the selection is on one it moves to the next item that can be.
+        let mut selected = state.selected;
+        let mut skipping = false;
+        let mut headers = Vec::new();
+        let mut keys = Vec::new();
+        // The marks are moved out while the state is borrowed by the window, and put back after
+        let marked = std::mem::take(&mut state.marked);
+        let frame = state.frame;
+        let cursor = state.line_cursor;
+        let selected_lines = std::cell::Cell::new(0);
+        let rules = (!self.style_rules.is_empty()).then(|| style_rule::RuleContext::new(state));
+        let dim_style = self.filter.map(|f| f.dim_style).unwrap_or_default();
+        let iter = items.map(|(i, mut it, matched)| {
+            if let Some(key) = &it.key {
+                keys.push((i, key.clone()));
+            }
+            if it.header {
+                headers.push(i);
+                skipping |= Some(i) == selected;
+            } else if skipping && matched {
+                selected = Some(i);
+                skipping = false;
+            }
+
+            if !matched {
+                it.style = it.style.patch(dim_style);
+            }
+            let base = match &rules {
+                Some(rules) if !it.header => {
+                    self.default_style.patch(rules.style(&self.style_rules, i))
+                }
+                _ => self.default_style,
+            };
+            if it.header {
+                it.style = base.patch(self.header_style.patch(it.style));
+            } else if Some(i) == selected || marked.contains(&i) {
+                it = it.indicators(self.selected_indicator.clone());
+                it.style = base.patch(it.style.patch(self.selected_style));
             } else {
-                it.style = self.default_style.patch(it.style);
+                it.style = base.patch(it.style);
+            }
+
+            it.indicators = it.indicators.at_frame(frame);
+            if let Some(mode) = self.wrap {
+                it.content = spans::wrap_text(it.content, text_width, mode);
             }
 
-            line_iters::ToLines::new(it, i == selected)
+            let is_selected = Some(i) == selected && !it.header;
+            let mut lines = line_iters::ToLines::new(it, i, is_selected);
+            if is_selected {
+                // Only the cursor line of an item taller than the window needs to be displayed
+                let count = lines.line_count();
+                selected_lines.set(count);
+                let line = if cursor.0 == i { cursor.1 } else { 0 };
+                let style = self.cursor_line_style.unwrap_or_default();
+                lines = lines.cursor(line, style, count > area.height as usize);
+            }
+            lines
         });
 
-        // Next step of pipeline, apply DisplayLine renderer
-        let item_display = self.item_display.display_iter(iter, sep);
+        // Next step of pipeline, apply DisplayLine renderer. Then drop any lines that were only
+        // generated for context, keeping their headers for pinning.
+        let first_item = start.first_item;
+        let header_lines = std::cell::RefCell::new(Vec::new());
+        let mut item_display = self
+            .item_display
+            .display_iter(
+                iter,
+                area.width as usize,
+                self.default_style,
+                self.bottom_up,
+            )
+            .filter(|l| {
+                let before = l.kind.is_before(first_item);
+                if before && l.is_header_start() {
+                    header_lines.borrow_mut().push(l.clone());
+                }
+                !before
+            })
+            .peekable();
+
+        // Find the line the window should start at, keeping the lines before it. For a
+        // scroll_to, only a window's worth of lines before the item is kept, so the window can
+        // still be filled near the end of the list.
+        let height = area.height as usize;
+        let mut context = Vec::new();
+        let mut skipped = 0;
+        if let Some((index, row)) = state.take_scroll_request(height) {
+            let mut before = BoundedVecDeque::new(height);
+            while let Some(l) = item_display.next_if(|l| l.kind.is_before(index)) {
+                skipped += 1;
+                if let Some(dropped) = before.push_back(l) {
+                    if dropped.is_header_start() {
+                        header_lines.borrow_mut().push(dropped);
+                    }
+                }
+            }
+            skipped -= before.len();
+            state.set_pos(before.len().saturating_sub(row));
+            context.extend(before);
+        } else if let Some((item, line)) = start.goal {
+            while let Some(l) = item_display.next_if(|l| l.kind.is_before(item)) {
+                context.push(l);
+            }
+            state.set_pos(context.len() + line);
+        }
+        let item_display = context.into_iter().chain(item_display);
+        state.apply_scroll_lines();
+
+        // Keep the first line of every header for pinning. Once in a section, the line above the
+        // selection is kept in the window too, so the pinned header doesn't cover the selection.
+        let sticky = self.sticky_headers.then_some(&header_lines);
+        let keep_above = matches!(
+            self.window_type,
+            WindowType::SelectionScroll | WindowType::Follow
+        );
+        let item_display = pin_headers(item_display, sticky, keep_above);
 
         // Filter the lines to those in the current view window
-        let lines = self
-            .window_type
-            .line_iter(item_display, area.height as usize, state);
+        let mut lines = self.window_type.line_iter(
+            item_display,
+            area.height as usize,
+            state,
+            self.scrollbar.is_some() && start.total_lines.is_none(),
+        );
+        state.window_first += start.base_line + skipped;
+        if let Some(total) = state.total_lines.as_mut() {
+            *total += skipped;
+        }
+        if start.total_lines.is_some() {
+            state.total_lines = start.total_lines;
+        }
+        state.set_headers(headers, selected);
+        state.set_selected_lines(selected_lines.get());
+        // Taken before a header is pinned over the first line
+        let separated = self.item_display.is_separated();
+        let anchor = layout::anchor(lines.iter().map(|l| l.kind), separated);
+        if self.sticky_headers && !self.bottom_up {
+            pin_header(&mut lines, header_lines.into_inner(), state.selected);
+        }
+        let follow = matches!(self.window_type, WindowType::Follow);
+        state.set_following(follow);
+
+        if let (Some(sb), Some(sb_area), Some(total)) =
+            (self.scrollbar, scrollbar_area, state.total_lines)
+        {
+            let mut first = state.window_first;
+            if self.bottom_up {
+                first = total.saturating_sub(first + lines.len());
+            }
+            sb.render(sb_area, buf, first, lines.len(), total);
+        }
+
+        let (left_width, right_width) = wrap_widths.unwrap_or_else(|| {
+            let items = lines.iter().filter(|l| l.kind.is_item());
+            self.indicator_widths(
+                items.map(|l| (l.left_indicator.width(), l.right_indicator.width())),
+            )
+        });
+        let text_width = area.width.saturating_sub(gutter + left_width + right_width) as usize;
+
+        // Horizontal scrolling is bounded by the widest item line in the window. Separators
+        // always span the whole list, so they don't scroll.
+        let item_width = |selected_only: bool| {
+            lines
+                .iter()
+                .filter(|l| match l.kind {
+                    LineKind::Item { index, .. } => !selected_only || Some(index) == state.selected,
+                    _ => false,
+                })
+                .map(|l| l.line.width())
+                .max()
+                .unwrap_or(0)
+        };
+        state.set_h_bounds(item_width(false), item_width(true), text_width);
+        let h_offset = state.h_offset;
+
+        state.layout = layout::ListLayout {
+            area,
+            gutter_width: gutter,
+            left_width,
+            right_width,
+            rows: lines.iter().map(|l| l.kind).collect(),
+            grid: None,
+        };
+        if let Some(anchor) = anchor {
+            state.window_anchor = anchor;
+        }
+        state.set_keys(&keys);
+
+        // Flip the window for a bottom up list, padding the top if it isn't full
+        let mut pad = 0;
+        if self.bottom_up {
+            lines = reverse_items(lines);
+            pad = (area.height as usize).saturating_sub(lines.len());
+            let filler = std::iter::repeat_n(LineKind::Filler, pad);
+            state.layout.rows = filler
+                .chain(lines.iter().map(|l| l.kind.flipped()))
+                .collect();
+        }
+
+        // The items numbered in the gutter: the first line of each item that isn't a header
+        let numbered: Vec<usize> = lines
+            .iter()
+            .filter_map(|l| match l.kind {
+                LineKind::Item { index, line: 0, .. } if !l.header => Some(index),
+                _ => None,
+            })
+            .collect();
+        let mut numbered_lines = 0;
+        state.hints = match self.numbering {
+            Some(Numbering::Hint) => numbered.clone(),
+            _ => Vec::new(),
+        };
 
         // Draw the lines into the window.
-        for (i, l) in lines.into_iter().enumerate() {
-            let y = area.y + i as u16;
+        let gutter = gutter.min(area.width);
+        let left_width = left_width.min(area.width - gutter);
+        let right_width = right_width.min(area.width - gutter - left_width);
+        for (i, l) in lines.drain(..).enumerate() {
+            let y = area.y + (pad + i) as u16;
             // first fill the whole line area
             let d_area = Rect {
                 x: area.x,
@@ -223,21 +900,81 @@ where
             let mut x = area.x;
             let mut line_width = area.width;
 
+            // show the number, right aligned in the gutter
+            if let Some(numbering) = self.numbering.filter(|_| gutter > 0) {
+                if let LineKind::Item { index, line: 0, .. } = l.kind {
+                    if !l.header {
+                        let number = numbering.number(state, index, numbered_lines, numbered.len());
+                        let text = format!("{number:>0$} ", gutter as usize - 1);
+                        buf.set_stringn(x, y, text, gutter as usize, Style::default());
+                        numbered_lines += 1;
+                    }
+                }
+                x += gutter;
+                line_width -= gutter;
+            }
+
+            // Separators span the whole list, so their indicator fills the column
+            let fill = |indicator: Spans<'a>, width: u16| match l.kind.is_item() {
+                true => indicator,
+                false => indicator
+                    .0
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+                    .repeat(width as usize)
+                    .into(),
+            };
+
             // show the left indicator and adjust the display area for the item text
-            if self.show_left_indicator {
-                buf.set_spans(x, y, &l.left_indicator, 1);
-                x += 1;
-                line_width -= 1;
+            if left_width > 0 {
+                buf.set_spans(x, y, &fill(l.left_indicator, left_width), left_width);
+                x += left_width;
+                line_width -= left_width;
             }
 
             // show the right indicator and adjust the display area for the item text
-            if self.show_right_indicator {
-                line_width -= 1;
-                buf.set_spans(x + line_width, y, &l.right_indicator, 1);
+            if right_width > 0 {
+                line_width -= right_width;
+                let indicator = fill(l.right_indicator, right_width);
+                buf.set_spans(x + line_width, y, &indicator, right_width);
+            }
+
+            if !l.kind.is_item() {
+                buf.set_spans(x, y, &l.line, line_width);
+                continue;
             }
 
-            // show the item text
-            buf.set_spans(x, y, &l.line, line_width);
+            // show the item text, scrolled to the offset
+            let full_width = l.line.width();
+            let line = match h_offset {
+                0 => l.line,
+                _ => spans::slice(&l.line, h_offset, line_width as usize),
+            };
+            buf.set_spans(x, y, &line, line_width);
+
+            // mark the ends of the line if any of the text is hidden
+            if let Some((left, right)) = self.overflow_markers {
+                if h_offset > 0 && full_width > 0 {
+                    buf.set_span(x, y, &Span::raw(left), line_width);
+                }
+                if full_width > h_offset + line_width as usize {
+                    let w = min(Span::raw(right).width() as u16, line_width);
+                    buf.set_span(x + line_width - w, y, &Span::raw(right), w);
+                }
+            }
+        }
+        state.marked = marked;
+
+        // While a followed list is detached from the end, show how much has been added
+        let new = state.new_items();
+        if follow && !state.is_following() && new > 0 && area.height > 0 {
+            let plural = if new == 1 { "" } else { "s" };
+            let text = format!(" {new} new line{plural} ");
+            let width = min(text.len() as u16, area.width);
+            let x = area.right() - width;
+            let y = area.bottom() - 1;
+            buf.set_stringn(x, y, text, width as usize, self.new_lines_style);
         }
     }
 }
@@ -252,6 +989,88 @@ where
     }
 }
 
+impl<'a, S> Widget for StyledList<'a, Source<S>>
+where
+    S: ItemSource<'a>,
+{
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = ListState::default();
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
+
+/// Pass the display lines through, collecting the first line of each header into `headers` if
+/// it is set. If `keep_above` is set, the line above the selection is marked to be displayed once
+/// a header has been seen.
+fn pin_headers<'a, 'h, I>(
+    lines: I,
+    headers: Option<&'h std::cell::RefCell<Vec<DisplayLine<'a>>>>,
+    keep_above: bool,
+) -> impl Iterator<Item = DisplayLine<'a>> + 'h
+where
+    I: Iterator<Item = DisplayLine<'a>> + 'h,
+    'a: 'h,
+{
+    let mut lines = lines.peekable();
+    std::iter::from_fn(move || {
+        let mut l = lines.next()?;
+        if let Some(headers) = headers {
+            if l.is_header_start() {
+                headers.borrow_mut().push(l.clone());
+            }
+            let in_section = !headers.borrow().is_empty();
+            if keep_above && in_section && lines.peek().is_some_and(|n| n.must_display) {
+                l.must_display = true;
+            }
+        }
+        Some(l)
+    })
+}
+
+/// Reverse the order of the items in `lines`, keeping the lines of each item in order. This turns
+/// the window upside down for [`StyledList::bottom_up`].
+fn reverse_items(lines: Vec<DisplayLine>) -> Vec<DisplayLine> {
+    let mut groups: Vec<Vec<DisplayLine>> = Vec::new();
+    for l in lines {
+        match (groups.last_mut(), l.kind) {
+            (Some(group), LineKind::Item { index, .. }) if matches!(group[0].kind, LineKind::Item { index: i, .. } if i == index) => {
+                group.push(l)
+            }
+            _ => groups.push(vec![l]),
+        }
+    }
+    groups.into_iter().rev().flatten().collect()
+}
+
+/// Draw the header of the section at the top of the window on the first row, unless it is
+/// already there or the selection is.
+fn pin_header<'a>(
+    lines: &mut [DisplayLine<'a>],
+    headers: Vec<DisplayLine<'a>>,
+    selected: Option<usize>,
+) {
+    let Some(top) = lines.first() else {
+        return;
+    };
+    let top_item = match top.kind {
+        LineKind::Item { index, .. } if Some(index) == selected => return,
+        LineKind::Item { index, .. } => index,
+        LineKind::Separator {
+            above: Some(above), ..
+        } => above,
+        _ => return,
+    };
+    let header = headers.into_iter().rev().find(|h| match h.kind {
+        LineKind::Item { index, .. } => index <= top_item,
+        _ => false,
+    });
+    if let Some(header) = header {
+        if header.kind != top.kind {
+            lines[0] = header;
+        }
+    }
+}
+
 // Private impls of conveience functions in render
 impl<'a> DisplayLine<'a> {
     /// Construct an empty DisplayLine (e.g as a placeholder)
@@ -260,20 +1079,80 @@ impl<'a> DisplayLine<'a> {
             style: Style::default(),
             line: Spans::from(x),
             must_display: false,
+            kind: LineKind::Filler,
             left_indicator: Spans::from(x),
             right_indicator: Spans::from(x),
+            header: false,
+        }
+    }
+
+    /// Whether this is the first line of a header, the line that gets pinned
+    fn is_header_start(&self) -> bool {
+        self.header && matches!(self.kind, LineKind::Item { line: 0, .. })
+    }
+}
+
+impl LineKind {
+    fn is_item(&self) -> bool {
+        matches!(self, LineKind::Item { .. })
+    }
+
+    /// The line as seen upside down: separators have the items on either side swapped.
+    fn flipped(self) -> Self {
+        match self {
+            LineKind::Separator { above, below } => LineKind::Separator {
+                above: below,
+                below: above,
+            },
+            kind => kind,
+        }
+    }
+
+    /// Does this line come before the item at `index`?
+    fn is_before(&self, index: usize) -> bool {
+        match *self {
+            LineKind::Item { index: i, .. } => i < index,
+            LineKind::Separator { below: Some(b), .. } => b < index,
+            _ => false,
         }
     }
 }
 
 impl ItemDisplay {
-    fn display_iter<'a, I>(self, iter: I, sep: Separator) -> DisplayIter<'a, I>
+    /// Separate the items with lines drawn by `style`, see [`ItemDisplay::SeparatedBy`].
+    pub fn separated_by<S: SeparatorStyle + 'static>(style: S) -> Self {
+        ItemDisplay::SeparatedBy(Arc::new(style))
+    }
+
+    /// The style of the separator lines, if there are any.
+    fn separator(&self) -> Option<Arc<dyn SeparatorStyle>> {
+        match self {
+            ItemDisplay::Basic | ItemDisplay::Grid(_) => None,
+            ItemDisplay::Separated => Some(Arc::new(HalfBlock)),
+            ItemDisplay::SeparatedBy(style) => Some(style.clone()),
+        }
+    }
+
+    fn is_separated(&self) -> bool {
+        !matches!(self, ItemDisplay::Basic | ItemDisplay::Grid(_))
+    }
+
+    fn display_iter<'a, I>(
+        &self,
+        iter: I,
+        width: usize,
+        style: Style,
+        flipped: bool,
+    ) -> DisplayIter<'a, I>
     where
         I: IntoIterator<Item = line_iters::ToLines<'a>>,
     {
-        match self {
-            ItemDisplay::Basic => DisplayIter::Basic(line_iters::Basic::new(iter)),
-            ItemDisplay::Separated => DisplayIter::Separated(line_iters::Separated::new(iter, sep)),
+        match self.separator() {
+            None => DisplayIter::Basic(line_iters::Basic::new(iter)),
+            Some(kind) => {
+                let sep = Separator::new(kind, width, style).flipped(flipped);
+                DisplayIter::Separated(line_iters::Separated::new(iter, sep))
+            }
         }
     }
 }
@@ -302,19 +1181,1085 @@ where
 impl WindowType {
     /// Iterate through the rendered display lines and produce the ones that should be shown in the
     /// window.
+    ///
+    /// The window position is recorded in `list_state`. If `count_all` is set, the lines after the
+    /// window are also consumed, to record the total number of lines.
     fn line_iter<'a, I>(
-        self,
+        &self,
         items: I,
         window_size: usize,
         list_state: &mut ListState,
-    ) -> impl Iterator<Item = DisplayLine<'a>>
+        count_all: bool,
+    ) -> Vec<DisplayLine<'a>>
     where
         I: Iterator<Item = DisplayLine<'a>>,
     {
         use WindowType::*;
+        let mut seen = 0;
+        let mut items = items.inspect(|_| seen += 1);
+        let lines: Vec<DisplayLine> = match *self {
+            _ if list_state.is_pinned() => {
+                window_type::pinned(&mut items, window_size, list_state).collect()
+            }
+            SelectionScroll | Follow => {
+                window_type::selection_scroll(&mut items, window_size, list_state).collect()
+            }
+            Fixed(at) => window_type::fixed(&mut items, at, window_size, list_state).collect(),
+            ScrollOff(margin) => {
+                window_type::scroll_off(&mut items, margin, window_size, list_state).collect()
+            }
+            Centered => window_type::centered(&mut items, window_size, list_state).collect(),
+        };
+
+        let total = if count_all {
+            items.for_each(drop);
+            Some(seen)
+        } else {
+            None
+        };
+        list_state.set_window(lines.len(), total);
+        lines
+    }
+
+    /// The first item that could be shown in a window of `height` lines, and the line the window
+    /// should try to start at, as an item and a line within it. `top` is the first line shown by
+    /// the last render.
+    ///
+    /// Every item takes at least one line, so starting `height` items before anything that must
+    /// be shown is always enough to fill the window.
+    fn first_item(
+        &self,
+        selected: usize,
+        top: (usize, usize),
+        height: usize,
+        separated: bool,
+    ) -> (usize, Option<(usize, usize)>) {
         match self {
-            SelectionScroll => window_type::selection_scroll(items, window_size, list_state),
-            Fixed(at) => window_type::fixed(items, at, window_size, list_state),
+            WindowType::SelectionScroll | WindowType::ScrollOff(_) | WindowType::Follow => {
+                let (top_item, _) = top;
+                // The window won't start below the first line of the selection
+                let goal = if selected < top_item {
+                    (selected, usize::from(separated))
+                } else {
+                    top
+                };
+                let first = goal.0.max(selected.saturating_sub(height));
+                let first = first.saturating_sub(height);
+                (first, Some(goal).filter(|&(item, _)| item >= first))
+            }
+            WindowType::Fixed(at) => (selected.saturating_sub(*at), None),
+            WindowType::Centered => (selected.saturating_sub(height), None),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ratatui::{style::Color, symbols::bar::HALF, text::Text};
+
+    fn render_list<'a>(list: StyledList<'a, Vec<ListItem<'a>>>, state: &mut ListState) -> Buffer {
+        let area = Rect::new(0, 0, 5, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(list, area, &mut buf, state);
+        buf
+    }
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf.get(x, y).symbol.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn wrapped_selection_stays_in_window() {
+        let items = vec![
+            ListItem::new("a"),
+            ListItem::new("b"),
+            ListItem::new("c"),
+            ListItem::new("dd ee ff"),
+        ];
+        let list = StyledList::new(items)
+            .wrap(WrapMode::Word)
+            .selected_indicator(LineIndicators::default().set_left(Indicator::LastLine(">")))
+            .show_left_indicator();
+        let mut state = ListState::new(4);
+        state.select(3);
+
+        let buf = render_list(list, &mut state);
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec![" c   ", " dd  ", " ee  ", ">ff  "]);
+    }
+
+    #[test]
+    fn h_scroll_with_markers() {
+        let items = vec![ListItem::new("abcdefgh"), ListItem::new("ab")];
+        let list = || {
+            StyledList::new(items.clone())
+                .overflow_markers("<", ">")
+                .show_left_indicator()
+        };
+        let mut state = ListState::new(2);
+        let buf = render_list(list(), &mut state);
+        assert_eq!(row(&buf, 0), " abc>");
+        assert_eq!(row(&buf, 1), " ab  ");
+
+        state.scroll_right(2);
+        let buf = render_list(list(), &mut state);
+        assert_eq!(row(&buf, 0), " <de>");
+        assert_eq!(row(&buf, 1), " <   ");
+
+        state.scroll_line_end();
+        let buf = render_list(list(), &mut state);
+        assert_eq!(state.h_offset(), 4);
+        assert_eq!(row(&buf, 0), " <fgh");
+    }
+
+    #[test]
+    fn scrollbar_tracks_window() {
+        let items: Vec<ListItem> = ('a'..='p').map(|c| ListItem::new(c.to_string())).collect();
+        let list = || StyledList::new(items.clone()).scrollbar(Scrollbar::default());
+        let mut state = ListState::new(items.len());
+
+        let buf = render_list(list(), &mut state);
+        assert_eq!(state.total_lines(), Some(items.len()));
+        assert_eq!(state.visible_lines(), 0..4);
+        let bar: Vec<&str> = (0..4).map(|y| buf.get(4, y).symbol.as_str()).collect();
+        assert_eq!(bar, vec!["█", "│", "│", "│"]);
+
+        state.select(items.len() - 1);
+        let buf = render_list(list(), &mut state);
+        assert_eq!(state.visible_lines(), items.len() - 4..items.len());
+        let bar: Vec<&str> = (0..4).map(|y| buf.get(4, y).symbol.as_str()).collect();
+        assert_eq!(bar, vec!["│", "│", "│", "█"]);
+        assert_eq!(row(&buf, 3), "p   █");
+    }
+
+    #[test]
+    fn filter_hides_and_highlights() {
+        let items = vec![
+            ListItem::new("apple"),
+            ListItem::new("banana"),
+            ListItem::new("cherry"),
+            ListItem::new("grape"),
+        ];
+        let hl = Style::default().fg(Color::Red);
+        let list =
+            |mode| StyledList::new(items.clone()).filter(Filter::new(mode).highlight_style(hl));
+        let mut state = ListState::new(4);
+        state.select(2);
+        state.set_filter("ape");
+
+        let buf = render_list(list(FilterMode::Hide), &mut state);
+        assert_eq!(row(&buf, 0), "apple");
+        assert_eq!(row(&buf, 1), "grape");
+        assert_eq!(row(&buf, 2), "     ");
+        // the selection moved to the best match
+        assert_eq!(state.selected(), Some(3));
+        let fgs: Vec<Color> = (0..5).map(|x| buf.get(x, 1).fg).collect();
+        assert_eq!(
+            fgs,
+            vec![
+                Color::Reset,
+                Color::Reset,
+                Color::Red,
+                Color::Red,
+                Color::Red
+            ]
+        );
+
+        state.cycle_next();
+        assert_eq!(state.selected(), Some(0));
+
+        let dim = Filter::new(FilterMode::Dim).dim_style(Style::default().fg(Color::Gray));
+        let buf = render_list(StyledList::new(items.clone()).filter(dim), &mut state);
+        assert_eq!(row(&buf, 1), "banan");
+        assert_eq!(buf.get(0, 1).fg, Color::Gray);
+
+        state.clear_filter();
+        assert_eq!(state.selected(), Some(0));
+        state.next();
+        assert_eq!(state.selected(), Some(1));
+    }
+
+    #[test]
+    fn separator_styles() {
+        let items = || vec![ListItem::new("a"), ListItem::new("b")];
+        let list = StyledList::new(items()).item_display(ItemDisplay::separated_by(Rule::SINGLE));
+        let buf = render_list(list, &mut ListState::new(2));
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["─────", "a    ", "─────", "b    "]);
+
+        let list = StyledList::new(items()).item_display(ItemDisplay::separated_by(Blank));
+        let buf = render_list(list, &mut ListState::new(2));
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["     ", "a    ", "     ", "b    "]);
+
+        // separators are not limited in width
+        let list = StyledList::new(items()).item_display(ItemDisplay::Separated);
+        let area = Rect::new(0, 0, 300, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(list, area, &mut buf, &mut ListState::new(2));
+        assert_eq!(row(&buf, 2), HALF.repeat(300));
+    }
+
+    #[test]
+    fn wide_indicators() {
+        let checked = Indicator::Char("[x]").style(Style::default().fg(Color::Green));
+        let items = vec![
+            ListItem::new("a").indicators(LineIndicators::default().set_left(checked.clone())),
+            ListItem::new("b\nc"),
+            ListItem::new("d"),
+        ];
+        let list = StyledList::new(items).show_left_indicator();
+        let mut state = ListState::new(3);
+        state.select(1);
+        let buf = render_list(list, &mut state);
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["[x]a ", "   b ", "   c ", "   d "]);
+        assert_eq!(buf.get(1, 0).fg, Color::Green);
+
+        // wrapped lines leave room for the widest indicator
+        let items = vec![
+            ListItem::new("a").indicators(LineIndicators::default().set_left(checked.clone())),
+            ListItem::new("bb cc"),
+        ];
+        let list = StyledList::new(items)
+            .wrap(WrapMode::Word)
+            .show_left_indicator();
+        let buf = render_list(list, &mut state);
+        let rows: Vec<String> = (0..3).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["[x]a ", "   bb", "   cc"]);
+
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buf = Buffer::empty(area);
+        let items = vec![ListItem::new("a"), ListItem::new("b\nc")];
+        let list = StyledList::new(items)
+            .selected_indicator(LineIndicators::default().set_right(Indicator::custom(
+                |line, lines, selected| {
+                    let c = if selected { '*' } else { ' ' };
+                    Span::raw(format!("{c}{}/{lines}", line + 1))
+                },
+            )))
+            .show_right_indicator();
+        StatefulWidget::render(list, area, &mut buf, &mut state);
+        let rows: Vec<String> = (0..3).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["a         ", "b     *1/2", "c     *2/2"]);
+        assert_eq!(
+            state.item_at(6, 1),
+            Some(Hit::Item {
+                index: 1,
+                line: 0,
+                column: HitColumn::RightIndicator
+            })
+        );
+    }
+
+    #[test]
+    fn spinner() {
+        const FRAMES: &[&str] = &["|", "/", "-", "\\"];
+        let list = || {
+            let spin = LineIndicators::default().set_left(Indicator::Spinner(FRAMES));
+            StyledList::new(vec![
+                ListItem::new("a"),
+                ListItem::new("b").indicators(spin),
+            ])
+            .show_left_indicator()
+        };
+        let mut state = ListState::new(2);
+        let buf = render_list(list(), &mut state);
+        assert_eq!(row(&buf, 1), "|b   ");
+        state.tick();
+        state.tick();
+        let buf = render_list(list(), &mut state);
+        assert_eq!(row(&buf, 1), "-b   ");
+    }
+
+    #[test]
+    fn numbering() {
+        let items = || {
+            let mut items: Vec<ListItem> = (0..11).map(|i| ListItem::new(i.to_string())).collect();
+            items[1] = ListItem::new("x\ny").style(Style::default().bg(Color::Red));
+            items
+        };
+        let area = Rect::new(0, 0, 6, 4);
+        let render = |numbering, state: &mut ListState| {
+            let mut buf = Buffer::empty(area);
+            let list = StyledList::new(items()).numbering(numbering);
+            StatefulWidget::render(list, area, &mut buf, state);
+            (0..4).map(|y| row(&buf, y)).collect::<Vec<String>>()
+        };
+
+        let mut state = ListState::new(11);
+        state.select(2);
+        assert_eq!(
+            render(Numbering::Absolute, &mut state),
+            vec![" 1 0  ", " 2 x  ", "   y  ", " 3 2  "]
+        );
+        assert_eq!(
+            render(Numbering::Relative, &mut state),
+            vec![" 2 0  ", " 1 x  ", "   y  ", " 0 2  "]
+        );
+        assert_eq!(
+            render(Numbering::Hint, &mut state),
+            vec!["a 0   ", "b x   ", "  y   ", "c 2   "]
+        );
+
+        // the gutter is drawn in the item's style
+        let mut buf = Buffer::empty(area);
+        let list = StyledList::new(items()).numbering(Numbering::Hint);
+        StatefulWidget::render(list, area, &mut buf, &mut state);
+        assert_eq!(buf.get(0, 1).bg, Color::Red);
+        assert_eq!(
+            state.item_at(0, 1),
+            Some(Hit::Item {
+                index: 1,
+                line: 0,
+                column: HitColumn::Gutter
+            })
+        );
+
+        assert!(state.select_hint("b"));
+        assert_eq!(state.selected(), Some(1));
+        assert!(!state.select_hint("z"));
+        assert_eq!(state.selected(), Some(1));
+    }
+
+    #[test]
+    fn follow() {
+        let area = Rect::new(0, 0, 14, 3);
+        let render = |ring: &ItemRing<'static>, state: &mut ListState| {
+            let list = StyledList::from_source(ring).window_type(WindowType::Follow);
+            let mut buf = Buffer::empty(area);
+            StatefulWidget::render(list, area, &mut buf, state);
+            (0..3).map(|y| row(&buf, y)).collect::<Vec<String>>()
+        };
+
+        let mut ring = ItemRing::new(5);
+        let mut state = ListState::new(1);
+        for i in 0..3 {
+            ring.push(ListItem::new(i.to_string()), &mut state);
+        }
+        state.select_last();
+        render(&ring, &mut state);
+        assert!(state.is_following());
+
+        // the oldest items are dropped, and the selection follows the end
+        for i in 3..7 {
+            ring.push(ListItem::new(i.to_string()), &mut state);
+        }
+        assert_eq!(ring.len(), 5);
+        assert_eq!(state.selected(), Some(4));
+        assert_eq!(
+            render(&ring, &mut state),
+            vec!["4             ", "5             ", "6             "]
+        );
+
+        // moving up stops following, and the new items are counted
+        state.prev();
+        render(&ring, &mut state);
+        assert!(!state.is_following());
+        ring.push(ListItem::new("7"), &mut state);
+        ring.push(ListItem::new("8"), &mut state);
+        assert_eq!(state.selected(), Some(1));
+        assert_eq!(
+            render(&ring, &mut state),
+            vec!["4             ", "5             ", "6 2 new lines "]
+        );
+
+        state.select_last();
+        render(&ring, &mut state);
+        assert!(state.is_following());
+        assert_eq!(state.new_items(), 0);
+
+        // clearing empties the state, and the next item is selected
+        ring.clear(&mut state);
+        assert_eq!(state.selected(), None);
+        ring.push(ListItem::new("9"), &mut state);
+        assert_eq!(state.selected(), Some(0));
+    }
+
+    #[test]
+    fn ring_keeps_line_cursor() {
+        let mut ring = ItemRing::new(3);
+        let mut state = ListState::new(0);
+        for i in 0..3 {
+            ring.push(ListItem::new(format!("{i}\n{i}")), &mut state);
+        }
+        state.select(2);
+        let area = Rect::new(0, 0, 5, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(StyledList::from_source(&ring), area, &mut buf, &mut state);
+        state.next_line();
+        assert_eq!(state.selected_line(), 1);
+
+        // the cursor stays on the same line of the item as it moves up
+        ring.push(ListItem::new("3"), &mut state);
+        assert_eq!(state.selected(), Some(1));
+        assert_eq!(state.selected_line(), 1);
+    }
+
+    #[test]
+    fn bottom_up() {
+        let items = || {
+            vec![
+                ListItem::new("a").style(Style::default().bg(Color::Red)),
+                ListItem::new("b\nc").style(Style::default().bg(Color::Blue)),
+                ListItem::new("d"),
+                ListItem::new("e"),
+            ]
+        };
+
+        // a short list sits against the bottom
+        let area = Rect::new(0, 0, 3, 5);
+        let mut buf = Buffer::empty(area);
+        let list = StyledList::new(items().into_iter().take(2)).bottom_up();
+        StatefulWidget::render(list, area, &mut buf, &mut ListState::new(2));
+        let rows: Vec<String> = (0..5).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["   ", "   ", "b  ", "c  ", "a  "]);
+
+        // the window moves up the screen with the selection
+        let mut state = ListState::new(4);
+        let list = || StyledList::new(items()).bottom_up();
+        let buf = render_list(list(), &mut state);
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["d    ", "b    ", "c    ", "a    "]);
+        state.select(3);
+        let buf = render_list(list(), &mut state);
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["e    ", "d    ", "b    ", "c    "]);
+        assert_eq!(state.visible_items(), 1..4);
+        assert_eq!(
+            state.item_at(0, 3),
+            Some(Hit::Item {
+                index: 1,
+                line: 1,
+                column: HitColumn::Content
+            })
+        );
+
+        // separators are colored for the items drawn above and below them
+        state.select(0);
+        let list = StyledList::new(items())
+            .item_display(ItemDisplay::Separated)
+            .bottom_up();
+        let buf = render_list(list, &mut state);
+        assert_eq!(row(&buf, 1), "▄▄▄▄▄");
+        assert_eq!(
+            (buf.get(0, 1).bg, buf.get(0, 1).fg),
+            (Color::Blue, Color::Red)
+        );
+        assert_eq!(
+            state.item_at(0, 1),
+            Some(Hit::Separator {
+                above: Some(1),
+                below: Some(0)
+            })
+        );
+    }
+
+    #[test]
+    fn style_rules() {
+        let items: Vec<ListItem> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(ListItem::new)
+            .collect();
+        let list = StyledList::new(items)
+            .default_style(Style::default().fg(Color::White))
+            .style_rule(StyleRule::FromSelection(|d| match d {
+                1 => Style::default().fg(Color::Yellow),
+                _ => Style::default(),
+            }))
+            .style_rule(StyleRule::Odd(Style::default().bg(Color::Gray)))
+            .style_rule(StyleRule::Last(Style::default().fg(Color::Red)))
+            .selected_style(Style::default().fg(Color::Blue))
+            .item_display(ItemDisplay::Separated);
+        let area = Rect::new(0, 0, 1, 9);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::new(4);
+        state.select(2);
+        StatefulWidget::render(list, area, &mut buf, &mut state);
+
+        let colors = |y| (buf.get(0, y).fg, buf.get(0, y).bg);
+        assert_eq!(colors(1), (Color::White, Color::Reset));
+        assert_eq!(colors(3), (Color::Yellow, Color::Gray));
+        assert_eq!(colors(5), (Color::Blue, Color::Reset));
+        assert_eq!(colors(7), (Color::Red, Color::Gray));
+        // the separators blend between the stripes
+        assert_eq!(colors(4).1, Color::Gray);
+        assert_eq!(colors(6), (Color::Gray, Color::Reset));
+    }
+
+    #[test]
+    fn line_cursor() {
+        let items = || {
+            vec![
+                ListItem::new("a"),
+                ListItem::new("b0\nb1\nb2\nb3\nb4\nb5"),
+                ListItem::new("c0\nc1"),
+            ]
+        };
+        let list = || StyledList::new(items()).cursor_line_style(Style::default().bg(Color::Red));
+        let mut state = ListState::new(3);
+        let buf = render_list(list(), &mut state);
+        assert_eq!(buf.get(0, 0).bg, Color::Red);
+
+        state.next_line();
+        assert_eq!((state.selected(), state.selected_line()), (Some(1), 0));
+        render_list(list(), &mut state);
+        for _ in 0..4 {
+            state.next_line();
+        }
+        assert_eq!(state.selected_line(), 4);
+
+        // the window scrolls within the item to keep the cursor line visible
+        let buf = render_list(list(), &mut state);
+        assert_eq!(row(&buf, 3), "b4   ");
+        assert_eq!(buf.get(0, 3).bg, Color::Red);
+        assert_eq!(buf.get(0, 2).bg, Color::Reset);
+
+        state.next_line();
+        render_list(list(), &mut state);
+        state.next_line();
+        assert_eq!((state.selected(), state.selected_line()), (Some(2), 0));
+        render_list(list(), &mut state);
+        state.next_line();
+        state.next_line();
+        assert_eq!((state.selected(), state.selected_line()), (Some(2), 1));
+
+        // moving back enters the previous item at its last line
+        state.prev_line();
+        state.prev_line();
+        assert_eq!((state.selected(), state.selected_line()), (Some(1), 5));
+
+        // other selection moves start over at the first line
+        state.select(2);
+        render_list(list(), &mut state);
+        state.select(1);
+        assert_eq!(state.selected_line(), 0);
+    }
+
+    #[test]
+    fn empty_text() {
+        let list = || {
+            StyledList::new(Vec::new())
+                .empty_text(Span::styled("none", Style::default().fg(Color::Red)))
+                .selected_indicator(LineIndicators::default().set_left(Indicator::Char(">")))
+                .show_left_indicator()
+        };
+        let mut state = ListState::new(0);
+        let buf = render_list(list(), &mut state);
+        let rows: Vec<String> = (0..4).map(|y| row(&buf, y)).collect();
+        assert_eq!(rows, vec!["     ", "none ", "     ", "     "]);
+        assert_eq!(buf.get(0, 1).fg, Color::Red);
+        assert_eq!(state.selected(), None);
+        assert_eq!(state.item_at(0, 1), None);
+        assert_eq!(state.visible_items(), 0..0);
+
+        // a source resizes the state, so it can become empty
+        let area = Rect::new(0, 0, 5, 4);
+        let mut state = ListState::new(3);
+        StatefulWidget::render(
+            StyledList::from_source(Vec::<ListItem>::new()),
+            area,
+            &mut Buffer::empty(area),
+            &mut state,
+        );
+        assert_eq!(state.selected(), None);
+    }
+
+    #[test]
+    fn keeps_selection_on_key() {
+        let items = |names: &[&'static str]| -> Vec<ListItem<'static>> {
+            names.iter().map(|&n| ListItem::new(n).key(n)).collect()
+        };
+        let mut state = ListState::new(4);
+        state.select(2);
+        render_list(StyledList::new(items(&["a", "b", "c", "d"])), &mut state);
+        assert_eq!(state.selected_key(), Some(&ItemKey::from("c")));
+
+        // an item is added above the selection
+        state.resize(5);
+        let buf = render_list(
+            StyledList::new(items(&["x", "a", "b", "c", "d"])),
+            &mut state,
+        );
+        assert_eq!(state.selected(), Some(3));
+        assert_eq!(row(&buf, 0), "a    ");
+
+        // the selected item is removed, so the item after it is selected
+        state.resize(4);
+        render_list(StyledList::new(items(&["x", "a", "b", "d"])), &mut state);
+        assert_eq!(state.selected(), Some(3));
+        assert_eq!(state.selected_key(), Some(&ItemKey::from("d")));
+
+        // moving the selection isn't undone, and the newly selected item is tracked
+        state.prev();
+        render_list(StyledList::new(items(&["x", "a", "b", "d"])), &mut state);
+        assert_eq!(state.selected_key(), Some(&ItemKey::from("b")));
+        state.resize(5);
+        render_list(
+            StyledList::new(items(&["y", "x", "a", "b", "d"])),
+            &mut state,
+        );
+        assert_eq!(state.selected(), Some(3));
+
+        // a source finds the key too, and the key can be restored
+        let mut state = ListState::new(5);
+        state.select_key("b");
+        let source = items(&["y", "x", "a", "b", "d"]);
+        let area = Rect::new(0, 0, 5, 4);
+        StatefulWidget::render(
+            StyledList::from_source(&source),
+            area,
+            &mut Buffer::empty(area),
+            &mut state,
+        );
+        assert_eq!(state.selected(), Some(3));
+    }
+
+    #[test]
+    fn hit_testing() {
+        let items = vec![ListItem::new("a\nb"), ListItem::new("c")];
+        let list = StyledList::new(items)
+            .block(Block::default().borders(ratatui::widgets::Borders::ALL))
+            .item_display(ItemDisplay::Separated)
+            .show_left_indicator();
+        let area = Rect::new(0, 0, 6, 9);
+        let mut buf = Buffer::empty(area);
+        let mut state = ListState::new(2);
+        StatefulWidget::render(list, area, &mut buf, &mut state);
+
+        // the border
+        assert_eq!(state.item_at(0, 1), None);
+        assert_eq!(
+            state.item_at(1, 1),
+            Some(Hit::Separator {
+                above: None,
+                below: Some(0)
+            })
+        );
+        assert_eq!(
+            state.item_at(1, 3),
+            Some(Hit::Item {
+                index: 0,
+                line: 1,
+                column: HitColumn::LeftIndicator
+            })
+        );
+        assert_eq!(
+            state.item_at(2, 5),
+            Some(Hit::Item {
+                index: 1,
+                line: 0,
+                column: HitColumn::Content
+            })
+        );
+        assert_eq!(
+            state.item_at(2, 4),
+            Some(Hit::Separator {
+                above: Some(0),
+                below: Some(1)
+            })
+        );
+        assert_eq!(
+            state.item_at(2, 6),
+            Some(Hit::Separator {
+                above: Some(1),
+                below: None
+            })
+        );
+        // blank space below the list
+        assert_eq!(state.item_at(2, 7), None);
+
+        let items = vec![ListItem::header("h"), ListItem::new("a")];
+        let mut state = ListState::new(2);
+        StatefulWidget::render(StyledList::new(items), area, &mut buf, &mut state);
+        assert_eq!(state.item_at(0, 0), Some(Hit::Header { index: 0 }));
+        assert_eq!(
+            state.item_at(0, 1),
+            Some(Hit::Item {
+                index: 1,
+                line: 0,
+                column: HitColumn::Content
+            })
+        );
+    }
+
+    #[test]
+    fn marked_items_use_selected_style() {
+        let items = vec![
+            ListItem::new("a"),
+            ListItem::new("b"),
+            ListItem::new("c"),
+            ListItem::new("d"),
+        ];
+        let list = StyledList::new(items).selected_style(Style::default().bg(Color::Blue));
+        let mut state = ListState::new(4);
+        state.select(1);
+        state.toggle_mark();
+        state.select(3);
+        state.toggle_mark();
+        state.select(0);
+
+        let buf = render_list(list, &mut state);
+        let bgs: Vec<Color> = (0..4).map(|y| buf.get(0, y).bg).collect();
+        assert_eq!(
+            bgs,
+            vec![Color::Blue, Color::Blue, Color::Reset, Color::Blue],
+        );
+    }
+
+    #[test]
+    fn sticky_headers() {
+        let items = || {
+            vec![
+                ListItem::header("A"),
+                ListItem::new("a1"),
+                ListItem::new("a2"),
+                ListItem::new("a3"),
+                ListItem::header("B"),
+                ListItem::new("b1"),
+            ]
+        };
+        let area = Rect::new(0, 0, 5, 3);
+        let mut state = ListState::new(6);
+        let render = |state: &mut ListState| {
+            let mut buf = Buffer::empty(area);
+            StatefulWidget::render(StyledList::new(items()), area, &mut buf, state);
+            (0..area.height)
+                .map(|y| row(&buf, y).trim_end().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // the selection moves off the header
+        assert_eq!(render(&mut state), vec!["A", "a1", "a2"]);
+        assert_eq!(state.selected(), Some(1));
+
+        state.next();
+        state.next();
+        assert_eq!(render(&mut state), vec!["A", "a2", "a3"]);
+
+        state.next();
+        assert_eq!(state.selected(), Some(5));
+        assert_eq!(render(&mut state), vec!["A", "B", "b1"]);
+
+        // scrolling up keeps the selection below the pinned header
+        state.prev();
+        assert_eq!(state.selected(), Some(3));
+        assert_eq!(render(&mut state), vec!["A", "a3", "B"]);
+
+        // the header is far before the window, which a list from a source doesn't build
+        let items = || {
+            std::iter::once(ListItem::header("HEAD"))
+                .chain((1..50).map(|i| ListItem::new(format!("i{i}"))))
+                .collect::<Vec<_>>()
+        };
+        let area = Rect::new(0, 0, 6, 4);
+        for source in [false, true] {
+            let mut state = ListState::new(50);
+            state.select(30);
+            let mut buf = Buffer::empty(area);
+            if source {
+                let list = StyledList::from_source(items());
+                StatefulWidget::render(list, area, &mut buf, &mut state);
+            } else {
+                StatefulWidget::render(StyledList::new(items()), area, &mut buf, &mut state);
+            }
+            let rows: Vec<String> = (0..4)
+                .map(|y| row(&buf, y).trim_end().to_string())
+                .collect();
+            assert_eq!(rows, vec!["HEAD", "i28", "i29", "i30"]);
+        }
+    }
+
+    #[test]
+    fn paging() {
+        let area = Rect::new(0, 0, 5, 7);
+        let render = |state: &mut ListState| {
+            let items = (0..10).map(|i| ListItem::new(Text::from(format!("{i}\n-"))));
+            let list = StyledList::new(items).item_display(ItemDisplay::Separated);
+            StatefulWidget::render(list, area, &mut Buffer::empty(area), state);
+        };
+        let mut state = ListState::new(10);
+        render(&mut state);
+        assert_eq!(state.window_height(), 7);
+        assert_eq!(state.visible_items(), 0..2);
+
+        state.page_down();
+        assert_eq!(state.selected(), Some(2));
+        render(&mut state);
+        assert_eq!(state.visible_items(), 1..3);
+
+        state.select_window_top();
+        assert_eq!(state.selected(), Some(1));
+        state.select_window_bottom();
+        assert_eq!(state.selected(), Some(2));
+        state.half_page_up();
+        assert_eq!(state.selected(), Some(1));
+        state.select_window_middle();
+        assert_eq!(state.selected(), Some(2));
+
+        state.select_last();
+        assert_eq!(state.selected(), Some(9));
+        state.page_up();
+        assert_eq!(state.selected(), Some(7));
+        state.select_first();
+        assert_eq!(state.selected(), Some(0));
+    }
+
+    #[test]
+    fn scroll_to() {
+        let area = Rect::new(0, 0, 5, 4);
+        let render = |state: &mut ListState| {
+            let items = (0..20).map(|i| ListItem::new(i.to_string()));
+            StatefulWidget::render(
+                StyledList::new(items),
+                area,
+                &mut Buffer::empty(area),
+                state,
+            );
+            state.visible_lines()
+        };
+        let mut state = ListState::new(20);
+        render(&mut state);
+
+        state.scroll_to(10, Align::Top);
+        assert_eq!(render(&mut state), 10..14);
+        assert_eq!(state.selected(), Some(0));
+        // the window stays put until the selection moves
+        assert_eq!(render(&mut state), 10..14);
+
+        state.scroll_to(10, Align::Bottom);
+        assert_eq!(render(&mut state), 7..11);
+        state.scroll_to(10, Align::Center);
+        assert_eq!(render(&mut state), 8..12);
+        state.scroll_to(19, Align::Top);
+        assert_eq!(render(&mut state), 16..20);
+
+        state.next();
+        assert_eq!(render(&mut state), 1..5);
+
+        state.select_and_scroll_to(15, Align::Center);
+        assert_eq!(render(&mut state), 13..17);
+        assert_eq!(state.selected(), Some(15));
+    }
+
+    #[test]
+    fn free_scroll() {
+        let area = Rect::new(0, 0, 5, 4);
+        let render = |state: &mut ListState| {
+            let items = (0..20).map(|i| ListItem::new(i.to_string()));
+            StatefulWidget::render(
+                StyledList::new(items),
+                area,
+                &mut Buffer::empty(area),
+                state,
+            );
+            state.visible_lines()
+        };
+        let mut state = ListState::new(20);
+        render(&mut state);
+
+        state.scroll_down(3);
+        assert_eq!(render(&mut state), 3..7);
+        assert_eq!(state.selected(), Some(0));
+        state.scroll_down(100);
+        assert_eq!(render(&mut state), 16..20);
+        state.scroll_up(2);
+        assert_eq!(render(&mut state), 14..18);
+
+        // moving the selection brings it back into view
+        state.next();
+        assert_eq!(render(&mut state), 1..5);
+    }
+
+    fn source_items() -> Vec<ListItem<'static>> {
+        (0..40)
+            .map(|i| {
+                if i % 10 == 4 {
+                    ListItem::header(format!("h{i}"))
+                } else if i % 3 == 0 {
+                    ListItem::new(Text::from(format!("{i}\n-")))
+                } else {
+                    ListItem::new(Text::from(format!("{i}")))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn source_matches_iterator() {
+        let moves: &[fn(&mut ListState)] = &[
+            |s| s.next(),
+            |s| s.select(20),
+            |s| s.next(),
+            |s| s.prev(),
+            |s| s.select(17),
+            |s| s.select(39),
+            |s| s.prev(),
+            |s| s.select(2),
+            |s| s.select(0),
+            |s| s.scroll_to(30, Align::Top),
+            |s| s.scroll_to(5, Align::Center),
+            |s| s.next(),
+            |s| s.select_and_scroll_to(25, Align::Bottom),
+            |s| s.scroll_to(39, Align::Top),
+            |s| s.select(10),
+            |s| s.scroll_down(7),
+            |s| s.scroll_up(12),
+            |s| s.scroll_down(100),
+            |s| s.prev(),
+        ];
+        for display in [ItemDisplay::Basic, ItemDisplay::Separated] {
+            for n in 0..4 {
+                let wt = || match n {
+                    0 => WindowType::SelectionScroll,
+                    1 => WindowType::Fixed(2),
+                    2 => WindowType::ScrollOff(1),
+                    _ => WindowType::Centered,
+                };
+                let mut iter_state = ListState::new(40);
+                let mut source_state = ListState::new(40);
+                for m in moves {
+                    m(&mut iter_state);
+                    m(&mut source_state);
+                    let list = StyledList::new(source_items())
+                        .item_display(display.clone())
+                        .window_type(wt());
+                    let iter_buf = render_list(list, &mut iter_state);
+                    let list = StyledList::from_source(source_items())
+                        .item_display(display.clone())
+                        .window_type(wt());
+                    let area = Rect::new(0, 0, 5, 4);
+                    let mut source_buf = Buffer::empty(area);
+                    StatefulWidget::render(list, area, &mut source_buf, &mut source_state);
+                    assert_eq!(iter_buf, source_buf);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn check<T: Send + Sync>() {}
+        check::<ListItem>();
+        check::<StyledList<Vec<ListItem>>>();
+        check::<ItemRing>();
+    }
+
+    struct CountingSource(std::cell::Cell<usize>);
+
+    impl<'a> ItemSource<'a> for CountingSource {
+        fn len(&self) -> usize {
+            1_000_000
+        }
+
+        fn item(&self, index: usize) -> ListItem<'a> {
+            self.0.set(self.0.get() + 1);
+            ListItem::new(Text::from(index.to_string()))
+        }
+    }
+
+    #[test]
+    fn source_builds_items_near_window() {
+        let mut state = ListState::new(1);
+        state.select(0);
+        let source = CountingSource(Default::default());
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(
+            StyledList::from_source(&source).scrollbar(Scrollbar::default()),
+            area,
+            &mut buf,
+            &mut state,
+        );
+
+        state.select(999_990);
+        source.0.set(0);
+        StatefulWidget::render(
+            StyledList::from_source(&source).scrollbar(Scrollbar::default()),
+            area,
+            &mut buf,
+            &mut state,
+        );
+        // twice the window height before the selection, the selection, an item for separator
+        // colors, and one to see the end of the selection
+        assert!(source.0.get() <= 2 * 4 + 3);
+        assert_eq!(state.visible_lines(), 999_987..999_991);
+        assert_eq!(state.total_lines(), Some(1_000_000));
+    }
+
+    /// Items of two lines, with the line counts worked out rather than added up
+    struct TallSource(std::cell::Cell<usize>);
+
+    impl<'a> ItemSource<'a> for TallSource {
+        fn len(&self) -> usize {
+            1_000_000
+        }
+
+        fn item(&self, index: usize) -> ListItem<'a> {
+            self.0.set(self.0.get() + 1);
+            ListItem::new(Text::from(format!("{index}\n-")))
+        }
+
+        fn lines_before(&self, index: usize) -> Option<usize> {
+            Some(2 * index)
+        }
+    }
+
+    #[test]
+    fn source_lines_before() {
+        let mut state = ListState::new(1_000_000);
+        state.select(500_000);
+        let source = TallSource(Default::default());
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(
+            StyledList::from_source(&source).scrollbar(Scrollbar::default()),
+            area,
+            &mut buf,
+            &mut state,
+        );
+        assert!(source.0.get() <= 2 * 4 + 3);
+        assert_eq!(state.visible_lines(), 999_998..1_000_002);
+        assert_eq!(state.total_lines(), Some(2_000_000));
+    }
+
+    /// Keyed items, after `inserted` items were added to the front
+    struct KeyedSource {
+        inserted: std::cell::Cell<usize>,
+        built: std::cell::Cell<usize>,
+    }
+
+    impl<'a> ItemSource<'a> for KeyedSource {
+        fn len(&self) -> usize {
+            1_000_000 + self.inserted.get()
+        }
+
+        fn item(&self, index: usize) -> ListItem<'a> {
+            self.built.set(self.built.get() + 1);
+            let key = index as i64 - self.inserted.get() as i64;
+            ListItem::new(Text::from(key.to_string())).key(key.to_string())
+        }
+    }
+
+    #[test]
+    fn source_finds_keys_nearby() {
+        let mut state = ListState::new(1_000_000);
+        state.select(500_000);
+        let source = KeyedSource {
+            inserted: Default::default(),
+            built: Default::default(),
+        };
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(StyledList::from_source(&source), area, &mut buf, &mut state);
+
+        source.inserted.set(3);
+        source.built.set(0);
+        StatefulWidget::render(StyledList::from_source(&source), area, &mut buf, &mut state);
+        assert_eq!(state.selected(), Some(500_003));
+        assert!(source.built.get() < 100);
+    }
+}
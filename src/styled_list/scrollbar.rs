@@ -0,0 +1,212 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    symbols::{block, line},
+};
+
+/// Which side of the list the [`Scrollbar`] is drawn on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollbarSide {
+    Left,
+    Right,
+}
+
+/// Where the [`Scrollbar`] column comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollbarPlacement {
+    /// Take a column from the inside of the list, narrowing the area for the items.
+    Inside,
+    /// Draw over the border of the list's [`Block`](ratatui::widgets::Block). If the block has no
+    /// border on the scrollbar's side, the scrollbar is placed inside.
+    Border,
+}
+
+/// A vertical scrollbar for a [`StyledList`](super::StyledList).
+///
+/// The thumb shows the position and size of the window relative to all the lines of the list.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Scrollbar {
+    pub(super) track_symbol: &'static str,
+    pub(super) thumb_symbol: &'static str,
+    pub(super) track_style: Style,
+    pub(super) thumb_style: Style,
+    pub(super) side: ScrollbarSide,
+    pub(super) placement: ScrollbarPlacement,
+}
+
+impl Scrollbar {
+    /// The symbol drawn for the part of the scrollbar outside of the thumb
+    pub fn track_symbol(mut self, symbol: &'static str) -> Self {
+        self.track_symbol = symbol;
+        self
+    }
+
+    /// The symbol drawn for the thumb
+    pub fn thumb_symbol(mut self, symbol: &'static str) -> Self {
+        self.thumb_symbol = symbol;
+        self
+    }
+
+    /// The style of the part of the scrollbar outside of the thumb
+    pub fn track_style(mut self, style: Style) -> Self {
+        self.track_style = style;
+        self
+    }
+
+    /// The style of the thumb
+    pub fn thumb_style(mut self, style: Style) -> Self {
+        self.thumb_style = style;
+        self
+    }
+
+    /// Set which side of the list the scrollbar is drawn on
+    pub fn side(mut self, side: ScrollbarSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Set whether the scrollbar is drawn inside the list or over the border
+    pub fn placement(mut self, placement: ScrollbarPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Split the scrollbar column from the list area. `outer` is the area of the whole widget,
+    /// and `inner` is the area inside the block. Returns the scrollbar column and the area left
+    /// for the list items.
+    pub(super) fn layout(&self, outer: Rect, inner: Rect) -> (Rect, Rect) {
+        let has_border = match self.side {
+            ScrollbarSide::Left => inner.x > outer.x,
+            ScrollbarSide::Right => inner.right() < outer.right(),
+        };
+        let column = |x| Rect {
+            x,
+            y: inner.y,
+            width: 1,
+            height: inner.height,
+        };
+
+        match (self.placement, self.side) {
+            (ScrollbarPlacement::Border, ScrollbarSide::Left) if has_border => {
+                (column(outer.x), inner)
+            }
+            (ScrollbarPlacement::Border, ScrollbarSide::Right) if has_border => {
+                (column(outer.right() - 1), inner)
+            }
+            _ if inner.width == 0 => (column(inner.x), inner),
+            (_, ScrollbarSide::Left) => {
+                let rest = Rect {
+                    x: inner.x + 1,
+                    width: inner.width - 1,
+                    ..inner
+                };
+                (column(inner.x), rest)
+            }
+            (_, ScrollbarSide::Right) => {
+                let rest = Rect {
+                    width: inner.width - 1,
+                    ..inner
+                };
+                (column(inner.right() - 1), rest)
+            }
+        }
+    }
+
+    /// Draw the scrollbar into `area` for a window showing `visible` lines, starting at line
+    /// `first` of `total`.
+    pub(super) fn render(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        first: usize,
+        visible: usize,
+        total: usize,
+    ) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let (start, len) = thumb(area.height as usize, first, visible, total);
+        for i in 0..area.height as usize {
+            let (symbol, style) = if i >= start && i < start + len {
+                (self.thumb_symbol, self.thumb_style)
+            } else {
+                (self.track_symbol, self.track_style)
+            };
+            buf.get_mut(area.x, area.y + i as u16)
+                .set_symbol(symbol)
+                .set_style(style);
+        }
+    }
+}
+
+impl Default for Scrollbar {
+    fn default() -> Self {
+        Scrollbar {
+            track_symbol: line::VERTICAL,
+            thumb_symbol: block::FULL,
+            track_style: Style::default(),
+            thumb_style: Style::default(),
+            side: ScrollbarSide::Right,
+            placement: ScrollbarPlacement::Inside,
+        }
+    }
+}
+
+/// Compute the start and length of the thumb in a track `track` cells long. The thumb is always
+/// at least one cell, and only touches the ends of the track when the window does.
+fn thumb(track: usize, first: usize, visible: usize, total: usize) -> (usize, usize) {
+    if total <= visible || total == 0 {
+        return (0, track);
+    }
+    let len = (track * visible / total).clamp(1, track);
+    let max_start = track - len;
+    let start = match first {
+        0 => 0,
+        _ if first + visible >= total => max_start,
+        // round to the nearest cell, but stay off the ends of the track
+        _ => ((2 * first * track + total) / (2 * total))
+            .max(1)
+            .min(max_start.saturating_sub(1)),
+    };
+    (start, len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn thumb_positions() {
+        // everything fits
+        assert_eq!(thumb(10, 0, 5, 5), (0, 10));
+        // at the top and bottom
+        assert_eq!(thumb(10, 0, 10, 100), (0, 1));
+        assert_eq!(thumb(10, 90, 10, 100), (9, 1));
+        // in the middle
+        assert_eq!(thumb(10, 50, 10, 100), (5, 1));
+        assert_eq!(thumb(10, 1, 10, 20), (1, 5));
+        assert_eq!(thumb(10, 9, 10, 20), (4, 5));
+    }
+
+    #[test]
+    fn layout() {
+        let outer = Rect::new(0, 0, 10, 5);
+        let inner = Rect::new(1, 1, 8, 3);
+        let sb = Scrollbar::default();
+        assert_eq!(
+            sb.layout(outer, inner),
+            (Rect::new(8, 1, 1, 3), Rect::new(1, 1, 7, 3))
+        );
+        let sb = sb.placement(ScrollbarPlacement::Border);
+        assert_eq!(sb.layout(outer, inner), (Rect::new(9, 1, 1, 3), inner));
+        let sb = sb.side(ScrollbarSide::Left);
+        assert_eq!(sb.layout(outer, inner), (Rect::new(0, 1, 1, 3), inner));
+
+        // no border to draw on
+        assert_eq!(
+            sb.layout(outer, outer),
+            (Rect::new(0, 0, 1, 5), Rect::new(1, 0, 9, 5))
+        );
+    }
+}
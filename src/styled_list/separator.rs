@@ -1,74 +1,248 @@
-use ratatui::{style::Style, symbols::bar::HALF};
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
 
-use super::DisplayLine;
+use ratatui::{
+    style::Style,
+    symbols::bar::HALF,
+    text::{Span, Spans},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::{DisplayLine, LineKind};
+
+/// The look of the separator lines drawn between items by
+/// [`ItemDisplay::SeparatedBy`](super::ItemDisplay::SeparatedBy).
+pub trait SeparatorStyle: Debug + Send + Sync {
+    /// The text of a separator line `width` columns wide.
+    fn line(&self, width: usize) -> Spans<'static>;
+
+    /// The text drawn in the indicator columns of a separator line.
+    fn indicator(&self) -> &'static str;
+
+    /// The style of a separator line. `above` and `below` are the styles of the items on either
+    /// side of it, which are `None` at the ends of the list. `default` is the default style of
+    /// the list.
+    fn style(&self, above: Option<Style>, below: Option<Style>, default: Style) -> Style;
+}
+
+/// A color transition between items, using the half block: "▄" to fill the line. The background
+/// is the background of the item above, and the foreground is the background of the item below.
+/// This is the separator used by [`ItemDisplay::Separated`](super::ItemDisplay::Separated).
+///
+/// The separators at the very beginning and end use the default style of the list in place of
+/// the missing item.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HalfBlock;
+
+impl SeparatorStyle for HalfBlock {
+    fn line(&self, width: usize) -> Spans<'static> {
+        HALF.repeat(width).into()
+    }
+
+    fn indicator(&self) -> &'static str {
+        HALF
+    }
+
+    fn style(&self, above: Option<Style>, below: Option<Style>, default: Style) -> Style {
+        let mut style = match default.bg {
+            Some(_) => Style::reset(),
+            None => Style::default(),
+        };
+        style.bg = above.map_or(default.bg, |s| s.bg);
+        style.fg = below.map_or(default.bg, |s| s.bg);
+        style
+    }
+}
+
+/// A horizontal rule drawn with a box drawing symbol, in the default style of the list.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    symbol: &'static str,
+}
+
+impl Rule {
+    pub const SINGLE: Rule = Rule::new("─");
+    pub const DOUBLE: Rule = Rule::new("═");
+    pub const DASHED: Rule = Rule::new("╌");
+
+    /// A rule drawn with `symbol`, which should be a single column wide.
+    pub const fn new(symbol: &'static str) -> Self {
+        Rule { symbol }
+    }
+}
+
+impl SeparatorStyle for Rule {
+    fn line(&self, width: usize) -> Spans<'static> {
+        self.symbol.repeat(width).into()
+    }
+
+    fn indicator(&self) -> &'static str {
+        self.symbol
+    }
+
+    fn style(&self, _above: Option<Style>, _below: Option<Style>, default: Style) -> Style {
+        default
+    }
+}
+
+/// An empty line in the default style of the list.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blank;
+
+impl SeparatorStyle for Blank {
+    fn line(&self, _width: usize) -> Spans<'static> {
+        Spans::default()
+    }
+
+    fn indicator(&self) -> &'static str {
+        " "
+    }
+
+    fn style(&self, _above: Option<Style>, _below: Option<Style>, default: Style) -> Style {
+        default
+    }
+}
+
+/// A [`Rule`] with a label in the middle, e.g. `──── • ────`. A label wider than the line is cut
+/// short.
+#[derive(Debug, Clone)]
+pub struct LabelledRule {
+    label: Cow<'static, str>,
+    rule: Rule,
+}
+
+impl LabelledRule {
+    pub fn new<L: Into<Cow<'static, str>>>(label: L) -> Self {
+        LabelledRule {
+            label: label.into(),
+            rule: Rule::SINGLE,
+        }
+    }
+
+    /// The rule drawn on either side of the label.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// The label, cut short to fit in `width` columns.
+    fn label(&self, width: usize) -> Cow<'static, str> {
+        if self.label.width() <= width {
+            return self.label.clone();
+        }
+        let mut used = 0;
+        let end = self
+            .label
+            .char_indices()
+            .find(|&(_, c)| {
+                used += c.width().unwrap_or(0);
+                used > width
+            })
+            .map_or(self.label.len(), |(i, _)| i);
+        self.label[..end].to_string().into()
+    }
+}
+
+impl SeparatorStyle for LabelledRule {
+    fn line(&self, width: usize) -> Spans<'static> {
+        // the label is padded with a space on each side, if there is room
+        let label = self.label(width);
+        let pad = if label.width() + 2 <= width { " " } else { "" };
+        let rule = width - label.width() - 2 * pad.len();
+        let left = rule / 2;
+        let symbol = self.rule.symbol;
+        Spans::from(vec![
+            Span::raw(symbol.repeat(left)),
+            Span::raw(pad),
+            Span::raw(label),
+            Span::raw(pad),
+            Span::raw(symbol.repeat(rule - left)),
+        ])
+    }
+
+    fn indicator(&self) -> &'static str {
+        self.rule.symbol
+    }
+
+    fn style(&self, above: Option<Style>, below: Option<Style>, default: Style) -> Style {
+        self.rule.style(above, below, default)
+    }
+}
 
 /// Generates separator lines.
 ///
-/// The separator line requires information about the previous line and the next line.
-/// It uses the half block: "▄" to fill the line. The background is the same as the
-/// background of the previous line, and the foreground is the background of the next
-/// line. This tracks the style last displayed, and when a new line is genrated width
-/// the next line's color, it "rotates" stored style so that the foreground (the bg Color
-/// of the previously displayed item) is the background, and the new color (the backround of the
-/// line which will be displayed after the separator) is set to foreground.
+/// The separator line requires information about the previous line and the next line. This
+/// tracks the style of the item last displayed, so the [`SeparatorStyle`] gets the styles on
+/// both sides of each line.
 ///
-/// This also stores the default style of the list, to set the separators at the
-/// very begining and very end to match the default style for the list widget.
-#[derive(Clone, Copy)]
+/// This also stores the default style of the list, for the separators at the very begining and
+/// very end.
+#[derive(Clone)]
 pub struct Separator {
+    kind: Arc<dyn SeparatorStyle>,
     width: usize,
     default_style: Style,
-    curr_style: Style,
+    prev_style: Option<Style>,
+    flipped: bool,
 }
 
 impl<'a> Separator {
-    pub(super) fn new(width: usize, style: Style) -> Self {
-        //style.fg(Color::Reset);
-        let default_style = match style.bg {
-            Some(init_color) => Style::reset().bg(init_color).fg(init_color),
-            None => Style::default(),
-        };
-
+    pub(super) fn new(kind: Arc<dyn SeparatorStyle>, width: usize, style: Style) -> Self {
         Separator {
+            kind,
             width,
-            default_style,
-            curr_style: default_style,
+            default_style: style,
+            prev_style: None,
+            flipped: false,
         }
     }
 
+    /// Style the lines to be drawn upside down, with the item above each line drawn below it.
+    pub(super) fn flipped(mut self, flipped: bool) -> Self {
+        self.flipped = flipped;
+        self
+    }
+
     pub(super) fn display_line(
         &mut self,
         must_display: bool,
         style: Option<Style>,
+        kind: LineKind,
     ) -> DisplayLine<'a> {
-        let style = style.unwrap_or(self.default_style);
-        self.curr_style.bg = self.curr_style.fg;
-        self.curr_style.fg = style.bg;
+        let mut above = std::mem::replace(&mut self.prev_style, style);
+        let mut below = style;
+        if self.flipped {
+            std::mem::swap(&mut above, &mut below);
+        }
+        let indicator = self.kind.indicator();
 
         DisplayLine {
-            style: self.curr_style,
-            line: gen_line(self.width).into(),
+            style: self.kind.style(above, below, self.default_style),
+            line: self.kind.line(self.width),
             must_display,
-            left_indicator: HALF.into(),
-            right_indicator: HALF.into(),
+            kind,
+            left_indicator: indicator.into(),
+            right_indicator: indicator.into(),
+            header: false,
         }
     }
 }
 
-// avoid allocation of a String per Separator::display_line call.
-lazy_static::lazy_static! {
-    static ref HALF_LINE: &'static str = {
-        let line = HALF.repeat(256);
-        let mem = Box::from(line);
-        Box::leak(mem)
-    };
-}
+#[cfg(test)]
+mod test {
+    use super::*;
 
-// Number of bytes in the HALF codepoint
-const HALF_SIZE: usize = HALF.as_bytes().len();
+    #[test]
+    fn any_width() {
+        assert_eq!(HalfBlock.line(300).width(), 300);
+        assert_eq!(Rule::DOUBLE.line(300).width(), 300);
+    }
 
-#[inline]
-fn gen_line(width: usize) -> &'static str {
-    // split takes the byte offset, but must be on codepoint boundaries
-    HALF_LINE.split_at(width * HALF_SIZE).0
+    #[test]
+    fn labelled() {
+        let text = |line: Spans| line.0.into_iter().map(|s| s.content).collect::<String>();
+        assert_eq!(text(LabelledRule::new("ab").line(9)), "── ab ───");
+        assert_eq!(text(LabelledRule::new("ab").line(3)), "ab─");
+        assert_eq!(text(LabelledRule::new("abc").line(2)), "ab");
+        assert_eq!(text(LabelledRule::new(7.to_string()).line(5)), "─ 7 ─");
+    }
 }
@@ -0,0 +1,206 @@
+use bounded_vec_deque::BoundedVecDeque;
+
+use super::{ItemKey, ListItem, ListState};
+
+/// Random access to the items of a list.
+///
+/// A [`StyledList`](super::StyledList) built from an iterator walks every item up to the window on
+/// each render. A list built with [`StyledList::from_source`](super::StyledList::from_source)
+/// instead starts building lines near the window, so the cost of rendering depends on the size
+/// of the window rather than the size of the list or the position of the selection.
+pub trait ItemSource<'a> {
+    /// The number of items in the list
+    fn len(&self) -> usize;
+
+    /// Is the list empty?
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the item at `index`. This is only called for items near the window.
+    fn item(&self, index: usize) -> ListItem<'a>;
+
+    /// The number of lines taken by the items before `index`, or `None` if it isn't known.
+    /// `lines_before(len())` is the height of the whole list.
+    ///
+    /// This is used to report the position of the window in lines (e.g. for
+    /// [`ListState::visible_lines`](super::ListState::visible_lines) and the
+    /// [`Scrollbar`](super::Scrollbar)). It is called for the window and for the whole list on
+    /// every render, so it should be cheap, e.g. a running total of the heights. By default
+    /// positions are counted as if every item is a single line.
+    fn lines_before(&self, _index: usize) -> Option<usize> {
+        None
+    }
+
+    /// The index of the last section header (see [`ListItem::header`]) at or before `index`. This
+    /// finds the header to pin for [`StyledList::sticky_headers`](super::StyledList::sticky_headers)
+    /// when it is before the window. The default is `None`, so sources with headers should
+    /// implement it.
+    fn header_before(&self, _index: usize) -> Option<usize> {
+        None
+    }
+
+    /// The index of the item with `key` (see [`ListItem::key`]), which was last seen at `near`.
+    /// This is used to find the selection again when the list renders, if the selected item has a
+    /// key.
+    ///
+    /// By default the items around `near` are built first, as items usually move only a little
+    /// between renders, and then every item until the key is found. Large sources whose items
+    /// can move far should override this to look the key up directly.
+    fn key_index(&self, key: &ItemKey, near: usize) -> Option<usize> {
+        find_near(self.len(), near, |i| self.item(i).key.as_ref() == Some(key))
+    }
+}
+
+impl<'a> ItemSource<'a> for Vec<ListItem<'a>> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn item(&self, index: usize) -> ListItem<'a> {
+        self[index].clone()
+    }
+
+    fn header_before(&self, index: usize) -> Option<usize> {
+        self.as_slice().header_before(index)
+    }
+
+    fn key_index(&self, key: &ItemKey, near: usize) -> Option<usize> {
+        self.as_slice().key_index(key, near)
+    }
+}
+
+impl<'a> ItemSource<'a> for [ListItem<'a>] {
+    fn len(&self) -> usize {
+        <[ListItem]>::len(self)
+    }
+
+    fn item(&self, index: usize) -> ListItem<'a> {
+        self[index].clone()
+    }
+
+    fn header_before(&self, index: usize) -> Option<usize> {
+        self[..=index].iter().rposition(|it| it.header)
+    }
+
+    fn key_index(&self, key: &ItemKey, near: usize) -> Option<usize> {
+        find_near(self.len(), near, |i| self[i].key.as_ref() == Some(key))
+    }
+}
+
+impl<'a, S> ItemSource<'a> for &S
+where
+    S: ItemSource<'a> + ?Sized,
+{
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn item(&self, index: usize) -> ListItem<'a> {
+        (**self).item(index)
+    }
+
+    fn lines_before(&self, index: usize) -> Option<usize> {
+        (**self).lines_before(index)
+    }
+
+    fn header_before(&self, index: usize) -> Option<usize> {
+        (**self).header_before(index)
+    }
+
+    fn key_index(&self, key: &ItemKey, near: usize) -> Option<usize> {
+        (**self).key_index(key, near)
+    }
+}
+
+/// A bounded store of items, which drops the oldest item when a new one is pushed while it is
+/// full. This is meant for lists that grow without end, like a log viewer using
+/// [`WindowType::Follow`](super::WindowType::Follow). Render it with
+/// [`StyledList::from_source`](super::StyledList::from_source).
+#[derive(Debug, Clone)]
+pub struct ItemRing<'a> {
+    items: BoundedVecDeque<ListItem<'a>>,
+}
+
+impl<'a> ItemRing<'a> {
+    /// An empty store holding at most `capacity` items.
+    pub fn new(capacity: usize) -> Self {
+        ItemRing {
+            items: BoundedVecDeque::new(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.max_len()
+    }
+
+    /// Add an item to the end, dropping the oldest item if the store is full. The indices in
+    /// `state` are moved along with the items (see [`ListState::remove_front`]), and it is resized
+    /// to the new length.
+    pub fn push(&mut self, item: ListItem<'a>, state: &mut ListState) {
+        if self.items.push_back(item).is_some() {
+            state.remove_front(1);
+        }
+        state.resize(self.items.len());
+    }
+
+    /// Remove every item, resizing `state` to 0 so it has no selection.
+    pub fn clear(&mut self, state: &mut ListState) {
+        self.items.clear();
+        state.resize(0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ListItem<'a>> {
+        self.items.iter()
+    }
+}
+
+impl<'a> ItemSource<'a> for ItemRing<'a> {
+    fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn item(&self, index: usize) -> ListItem<'a> {
+        self.items[index].clone()
+    }
+
+    fn header_before(&self, index: usize) -> Option<usize> {
+        self.items.iter().take(index + 1).rposition(|it| it.header)
+    }
+
+    fn key_index(&self, key: &ItemKey, near: usize) -> Option<usize> {
+        find_near(self.len(), near, |i| {
+            self.items[i].key.as_ref() == Some(key)
+        })
+    }
+}
+
+/// The items of a [`StyledList`](super::StyledList) created from an [`ItemSource`].
+pub struct Source<S>(pub(super) S);
+
+/// How far either side of where a key was last seen [`ItemSource::key_index`] looks before
+/// searching the whole list
+const KEY_PROBE: usize = 32;
+
+/// The first index in `0..len` for which `found` is true, trying the indices around `near` first.
+fn find_near(len: usize, near: usize, found: impl Fn(usize) -> bool) -> Option<usize> {
+    let near = near.min(len);
+    let around = (0..KEY_PROBE).flat_map(|d| [near.checked_add(d), near.checked_sub(d + 1)]);
+    around
+        .flatten()
+        .filter(|&i| i < len)
+        .find(|&i| found(i))
+        .or_else(|| (0..len).find(|&i| found(i)))
+}
+
+/// The number of display lines before the item at `n`. `separated` adds the separator line that
+/// comes before each item.
+pub(super) fn lines_before<'a, S: ItemSource<'a>>(source: &S, n: usize, separated: bool) -> usize {
+    let sep = usize::from(separated);
+    let lines = if source.is_empty() {
+        None
+    } else {
+        source.lines_before(n)
+    };
+    lines.unwrap_or(n) + n * sep
+}
@@ -0,0 +1,323 @@
+//! Helpers for working with [`Spans`] by display width rather than by bytes or chars.
+//!
+//! These operate on grapheme clusters, and always keep each piece of text in the [`Span`] (and
+//! so the style) it came from.
+use std::{borrow::Cow, ops::Range};
+
+use ratatui::{
+    style::Style,
+    text::{Span, Spans, Text},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::WrapMode;
+
+/// A grapheme cluster in a [`Spans`]: which span it is in, the byte range within that span's
+/// content, and how many columns it takes on display.
+#[derive(Clone, Debug)]
+struct Grapheme {
+    span: usize,
+    bytes: Range<usize>,
+    width: usize,
+    whitespace: bool,
+}
+
+fn graphemes(spans: &Spans) -> Vec<Grapheme> {
+    spans
+        .0
+        .iter()
+        .enumerate()
+        .flat_map(|(span, s)| {
+            s.content
+                .grapheme_indices(true)
+                .map(move |(start, g)| Grapheme {
+                    span,
+                    bytes: start..start + g.len(),
+                    width: g.width(),
+                    whitespace: g.chars().all(char::is_whitespace),
+                })
+        })
+        .collect()
+}
+
+/// Borrow a piece of the content if possible, otherwise copy it.
+fn sub_content<'a>(content: &Cow<'a, str>, bytes: Range<usize>) -> Cow<'a, str> {
+    match content {
+        Cow::Borrowed(s) => Cow::Borrowed(&s[bytes]),
+        Cow::Owned(s) => Cow::Owned(s[bytes].to_string()),
+    }
+}
+
+/// Build a new [`Spans`] from a run of graphemes, merging consecutive graphemes from the same
+/// span back into a single [`Span`].
+fn collect_spans<'a>(spans: &Spans<'a>, gs: &[Grapheme]) -> Spans<'a> {
+    let mut res: Vec<Span<'a>> = Vec::new();
+    let mut run: Option<(usize, Range<usize>)> = None;
+    for g in gs {
+        run = match run {
+            Some((span, bytes)) if span == g.span && bytes.end == g.bytes.start => {
+                Some((span, bytes.start..g.bytes.end))
+            }
+            Some((span, bytes)) => {
+                let s = &spans.0[span];
+                res.push(Span::styled(sub_content(&s.content, bytes), s.style));
+                Some((g.span, g.bytes.clone()))
+            }
+            None => Some((g.span, g.bytes.clone())),
+        };
+    }
+    if let Some((span, bytes)) = run {
+        let s = &spans.0[span];
+        res.push(Span::styled(sub_content(&s.content, bytes), s.style));
+    }
+    Spans(res)
+}
+
+/// Drop any whitespace at the end of the range.
+fn trim_end(gs: &[Grapheme], mut range: Range<usize>) -> Range<usize> {
+    while range.end > range.start && gs[range.end - 1].whitespace {
+        range.end -= 1;
+    }
+    range
+}
+
+/// Split a line into several lines, none wider than `width`.
+///
+/// In [`WrapMode::Word`] lines are broken at whitespace when possible, and words that are wider
+/// than `width` are broken wherever they need to be. Whitespace at a break is dropped.
+pub(super) fn wrap_line<'a>(spans: Spans<'a>, width: usize, mode: WrapMode) -> Vec<Spans<'a>> {
+    if width == 0 || spans.width() <= width {
+        return vec![spans];
+    }
+
+    let gs = graphemes(&spans);
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut line_width = 0;
+    // index of the first grapheme of the word currently being filled
+    let mut word_start: Option<usize> = None;
+    let mut i = 0;
+
+    while i < gs.len() {
+        let g = &gs[i];
+        if line_width + g.width > width && i > start {
+            match mode {
+                WrapMode::Char => {
+                    lines.push(start..i);
+                    start = i;
+                    line_width = 0;
+                }
+                WrapMode::Word if g.whitespace => {
+                    lines.push(trim_end(&gs, start..i));
+                    while i < gs.len() && gs[i].whitespace {
+                        i += 1;
+                    }
+                    start = i;
+                    line_width = 0;
+                    word_start = None;
+                    continue;
+                }
+                WrapMode::Word => match word_start {
+                    // move the word to the next line
+                    Some(w) if w > start => {
+                        lines.push(trim_end(&gs, start..w));
+                        start = w;
+                        line_width = gs[w..i].iter().map(|g| g.width).sum();
+                    }
+                    // the word doesn't fit on a line by itself, so break it up.
+                    _ => {
+                        lines.push(trim_end(&gs, start..i));
+                        start = i;
+                        line_width = 0;
+                        word_start = Some(i);
+                    }
+                },
+            }
+        }
+
+        if g.whitespace {
+            word_start = None;
+        } else if word_start.is_none() {
+            word_start = Some(i);
+        }
+        line_width += g.width;
+        i += 1;
+    }
+    if start < gs.len() {
+        lines.push(start..gs.len());
+    }
+
+    lines
+        .into_iter()
+        .map(|r| collect_spans(&spans, &gs[r]))
+        .collect()
+}
+
+/// Wrap every line of `text` with [`wrap_line`].
+pub(super) fn wrap_text(text: Text, width: usize, mode: WrapMode) -> Text {
+    let lines = text
+        .lines
+        .into_iter()
+        .flat_map(|l| wrap_line(l, width, mode))
+        .collect();
+    Text { lines }
+}
+
+/// Cut the columns `offset..offset + width` out of a line, for horizontal scrolling. Wide
+/// graphemes that only partly fit at either edge are replaced with spaces.
+pub(super) fn slice<'a>(spans: &Spans<'a>, offset: usize, width: usize) -> Spans<'a> {
+    let end = offset + width;
+    let mut col = 0;
+    let mut left_pad = None;
+    let mut right_pad = None;
+    let mut visible = Vec::new();
+    for g in graphemes(spans) {
+        let (start, stop) = (col, col + g.width);
+        col = stop;
+        if stop <= offset || start >= end {
+            continue;
+        }
+        let style = spans.0[g.span].style;
+        if start < offset {
+            left_pad = Some(Span::styled(" ".repeat(stop - offset), style));
+        } else if stop > end {
+            right_pad = Some(Span::styled(" ".repeat(end - start), style));
+        } else {
+            visible.push(g);
+        }
+    }
+
+    let mut res = collect_spans(spans, &visible);
+    if let Some(pad) = left_pad {
+        res.0.insert(0, pad);
+    }
+    res.0.extend(right_pad);
+    res
+}
+
+/// Patch `style` into the chars of a line at the char indices `chars`, which must be in
+/// ascending order. Spans are split as needed so the rest of the line keeps its styles.
+pub(super) fn patch_chars<'a>(spans: Spans<'a>, chars: &[usize], style: Style) -> Spans<'a> {
+    let mut chars = chars.iter().copied().peekable();
+    let mut offset = 0;
+    let mut res = Vec::new();
+    for span in spans.0 {
+        // byte ranges of the span, and whether they are patched
+        let mut runs: Vec<(Range<usize>, bool)> = Vec::new();
+        for (i, (byte, c)) in span.content.char_indices().enumerate() {
+            let patched = chars.next_if_eq(&(offset + i)).is_some();
+            let bytes = byte..byte + c.len_utf8();
+            match runs.last_mut() {
+                Some((r, p)) if *p == patched => r.end = bytes.end,
+                _ => runs.push((bytes, patched)),
+            }
+        }
+        offset += span.content.chars().count();
+
+        for (bytes, patched) in runs {
+            let s = if patched {
+                span.style.patch(style)
+            } else {
+                span.style
+            };
+            res.push(Span::styled(sub_content(&span.content, bytes), s));
+        }
+    }
+    Spans(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn contents(lines: &[Spans]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.0.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn wrap_chars() {
+        let lines = wrap_line(Spans::from("abcdefg"), 3, WrapMode::Char);
+        assert_eq!(contents(&lines), vec!["abc", "def", "g"]);
+    }
+
+    #[test]
+    fn wrap_words() {
+        let lines = wrap_line(Spans::from("the quick brown fox"), 10, WrapMode::Word);
+        assert_eq!(contents(&lines), vec!["the quick", "brown fox"]);
+
+        let lines = wrap_line(Spans::from("a verylongword b"), 5, WrapMode::Word);
+        assert_eq!(contents(&lines), vec!["a", "veryl", "ongwo", "rd b"]);
+    }
+
+    #[test]
+    fn wrap_short_line_unchanged() {
+        let lines = wrap_line(Spans::from("abc"), 3, WrapMode::Word);
+        assert_eq!(contents(&lines), vec!["abc"]);
+        let lines = wrap_line(Spans::from(""), 3, WrapMode::Word);
+        assert_eq!(contents(&lines), vec![""]);
+    }
+
+    #[test]
+    fn wrap_keeps_styles() {
+        let red = Style::default().fg(Color::Red);
+        let blue = Style::default().fg(Color::Blue);
+        let spans = Spans::from(vec![
+            Span::styled("ab cd", red),
+            Span::styled("ef gh", blue),
+        ]);
+        let lines = wrap_line(spans, 4, WrapMode::Word);
+        assert_eq!(
+            lines,
+            vec![
+                Spans::from(Span::styled("ab", red)),
+                Spans::from(vec![Span::styled("cd", red), Span::styled("ef", blue)]),
+                Spans::from(Span::styled("gh", blue)),
+            ]
+        );
+    }
+
+    #[test]
+    fn slices() {
+        let red = Style::default().fg(Color::Red);
+        let spans = Spans::from(vec![Span::raw("abc"), Span::styled("def", red)]);
+        assert_eq!(
+            slice(&spans, 2, 3),
+            Spans::from(vec![Span::raw("c"), Span::styled("de", red)])
+        );
+        assert_eq!(contents(&[slice(&spans, 4, 10)]), vec!["ef"]);
+        assert_eq!(contents(&[slice(&spans, 6, 10)]), vec![""]);
+    }
+
+    #[test]
+    fn slice_wide_graphemes() {
+        let spans = Spans::from("日本語");
+        assert_eq!(contents(&[slice(&spans, 1, 4)]), vec![" 本 "]);
+    }
+
+    #[test]
+    fn patches_chars() {
+        let red = Style::default().fg(Color::Red);
+        let bold = Style::default().add_modifier(ratatui::style::Modifier::BOLD);
+        let spans = Spans::from(vec![Span::raw("abc"), Span::styled("de", red)]);
+        assert_eq!(
+            patch_chars(spans, &[1, 2, 3], bold),
+            Spans::from(vec![
+                Span::raw("a"),
+                Span::styled("bc", bold),
+                Span::styled("d", red.patch(bold)),
+                Span::styled("e", red),
+            ])
+        );
+    }
+
+    #[test]
+    fn wrap_wide_graphemes() {
+        let lines = wrap_line(Spans::from("日本語"), 4, WrapMode::Char);
+        assert_eq!(contents(&lines), vec!["日本", "語"]);
+    }
+}
@@ -0,0 +1,66 @@
+use ratatui::style::Style;
+
+use super::ListState;
+
+/// A style applied to items by their position in the list, e.g. for zebra striping. See
+/// [`StyledList::style_rule`](super::StyledList::style_rule).
+///
+/// Positions are counted among the items matching the filter, so the stripes stay even when
+/// items are hidden. Section headers aren't styled by the rules.
+#[derive(Debug, Copy, Clone)]
+pub enum StyleRule {
+    /// Items at an even position, starting with the first item at 0
+    Even(Style),
+    /// Items at an odd position
+    Odd(Style),
+    /// The first item
+    First(Style),
+    /// The last item
+    Last(Style),
+    /// Every item, with the style returned for its distance from the selection in items. The
+    /// selected item is at 0.
+    FromSelection(fn(usize) -> Style),
+}
+
+/// What the rules need to know about the list, taken from the state before the items are
+/// styled.
+pub(super) struct RuleContext {
+    matches: Option<Vec<usize>>,
+    selected: usize,
+    last: usize,
+}
+
+impl RuleContext {
+    pub(super) fn new(state: &ListState) -> Self {
+        let matches = state.matches.clone();
+        RuleContext {
+            selected: state.matched_position(state.selected.unwrap_or(0)),
+            last: state.filtered_len().saturating_sub(1),
+            matches,
+        }
+    }
+
+    fn position(&self, index: usize) -> usize {
+        match &self.matches {
+            Some(m) => m.partition_point(|&i| i < index),
+            None => index,
+        }
+    }
+
+    /// The styles of the rules that apply to the item at `index`, patched together in order.
+    pub(super) fn style(&self, rules: &[StyleRule], index: usize) -> Style {
+        let position = self.position(index);
+        let odd = position % 2 == 1;
+        rules.iter().fold(Style::default(), |style, rule| {
+            let patch = match *rule {
+                StyleRule::Even(s) if !odd => s,
+                StyleRule::Odd(s) if odd => s,
+                StyleRule::First(s) if position == 0 => s,
+                StyleRule::Last(s) if position == self.last => s,
+                StyleRule::FromSelection(f) => f(position.abs_diff(self.selected)),
+                _ => return style,
+            };
+            style.patch(patch)
+        })
+    }
+}
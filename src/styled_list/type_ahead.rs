@@ -0,0 +1,224 @@
+use std::{
+    borrow::Borrow,
+    time::{Duration, Instant},
+};
+
+use super::{filter::plain_text, ListItem, ListState};
+
+/// The time source of a [`TypeAhead`]. Any `Fn() -> Instant` is a clock, so tests can control
+/// the time.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The system clock, from [`Instant::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
+/// Jump to an item by typing the start of its text, like in most file managers. See
+/// [`ListState::type_ahead`].
+///
+/// The typed characters are collected into a buffer, which starts over once no key has been
+/// typed for the timeout (a second by default). Matching ignores case.
+#[derive(Debug, Clone)]
+pub struct TypeAhead<C = SystemClock> {
+    buffer: String,
+    last: Option<Instant>,
+    timeout: Duration,
+    word_start: bool,
+    clock: C,
+}
+
+impl TypeAhead {
+    pub fn new() -> Self {
+        TypeAhead::with_clock(SystemClock)
+    }
+}
+
+impl Default for TypeAhead {
+    fn default() -> Self {
+        TypeAhead::new()
+    }
+}
+
+impl<C: Clock> TypeAhead<C> {
+    /// Type-ahead timed by `clock`.
+    pub fn with_clock(clock: C) -> Self {
+        TypeAhead {
+            buffer: String::new(),
+            last: None,
+            timeout: Duration::from_secs(1),
+            word_start: false,
+            clock,
+        }
+    }
+
+    /// The pause after which the next key starts a new search.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Match the start of any word in an item, rather than only the start of its text. Words
+    /// start after any character that isn't alphanumeric.
+    pub fn word_start(mut self, word_start: bool) -> Self {
+        self.word_start = word_start;
+        self
+    }
+
+    /// The characters typed since the buffer last started over.
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Start over with an empty buffer.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.last = None;
+    }
+
+    /// Add `c` to the buffer, starting over if the timeout has passed. Returns whether the
+    /// search continues from the previous characters.
+    fn push(&mut self, c: char) -> bool {
+        let now = self.clock.now();
+        let continued = self
+            .last
+            .is_some_and(|last| now.duration_since(last) < self.timeout);
+        if !continued {
+            self.buffer.clear();
+        }
+        self.buffer.extend(c.to_lowercase());
+        self.last = Some(now);
+        continued
+    }
+
+    /// Does the (lowercase) text of an item match the buffer?
+    fn matches(&self, text: &str) -> bool {
+        if !self.word_start {
+            return text.starts_with(&self.buffer);
+        }
+        let mut prev = None;
+        text.char_indices().any(|(i, c)| {
+            let start = !prev.is_some_and(char::is_alphanumeric);
+            prev = Some(c);
+            start && text[i..].starts_with(&self.buffer)
+        })
+    }
+}
+
+impl ListState {
+    /// Type `c` into `type_ahead`, and select the first item whose text matches the typed
+    /// characters. `items` are all the items of the list, in order. Returns whether a matching
+    /// item was found.
+    ///
+    /// A new search starts after the selected item, so typing the same letter again moves on to
+    /// the next item starting with it. Further characters keep the selection if it still
+    /// matches. Either way the search wraps around to the start of the list. Only items that can
+    /// be selected are searched, so section headers and items hidden by the filter are skipped.
+    pub fn type_ahead<'a, I, L, C>(
+        &mut self,
+        type_ahead: &mut TypeAhead<C>,
+        c: char,
+        items: I,
+    ) -> bool
+    where
+        I: IntoIterator<Item = L>,
+        L: Borrow<ListItem<'a>>,
+        C: Clock,
+    {
+        let continued = type_ahead.push(c);
+        let Some(selected) = self.selected else {
+            return false;
+        };
+        let start = if continued { selected } else { selected + 1 };
+        let mut wrapped = None;
+        for (i, it) in items.into_iter().enumerate() {
+            let it = it.borrow();
+            if it.header || !self.is_selectable(i) {
+                continue;
+            }
+            if !type_ahead.matches(&plain_text(&it.content).to_lowercase()) {
+                continue;
+            }
+            if i >= start {
+                self.selected = Some(i);
+                return true;
+            }
+            wrapped = wrapped.or(Some(i));
+        }
+        if wrapped.is_some() {
+            self.selected = wrapped;
+        }
+        wrapped.is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    fn items() -> Vec<ListItem<'static>> {
+        ["apple", "banana", "pear", "Papaya", "peach", "red plum"]
+            .into_iter()
+            .map(ListItem::new)
+            .collect()
+    }
+
+    #[test]
+    fn jumps_to_typed_prefix() {
+        let now = Rc::new(Cell::new(Instant::now()));
+        let clock = {
+            let now = now.clone();
+            move || now.get()
+        };
+        let mut ta = TypeAhead::with_clock(clock);
+        let mut state = ListState::new(6);
+        let items = items();
+
+        assert!(state.type_ahead(&mut ta, 'p', &items));
+        assert_eq!(state.selected(), Some(2));
+        assert!(state.type_ahead(&mut ta, 'A', &items));
+        assert_eq!(state.selected(), Some(3));
+        assert_eq!(ta.buffer(), "pa");
+        assert!(!state.type_ahead(&mut ta, 'x', &items));
+        assert_eq!(state.selected(), Some(3));
+
+        // after a pause the search starts over, after the selection
+        now.set(now.get() + Duration::from_secs(2));
+        assert!(state.type_ahead(&mut ta, 'p', &items));
+        assert_eq!(state.selected(), Some(4));
+        now.set(now.get() + Duration::from_secs(2));
+        assert!(state.type_ahead(&mut ta, 'p', &items));
+        assert_eq!(state.selected(), Some(2));
+    }
+
+    #[test]
+    fn word_start() {
+        let mut ta = TypeAhead::new().word_start(true);
+        let mut state = ListState::new(6);
+        assert!(state.type_ahead(&mut ta, 'p', items()));
+        assert_eq!(state.selected(), Some(2));
+        ta.reset();
+        state.select(4);
+        assert!(state.type_ahead(&mut ta, 'p', items()));
+        assert_eq!(state.selected(), Some(5));
+
+        // "n" is in "banana", but doesn't start a word
+        ta.reset();
+        assert!(!state.type_ahead(&mut ta, 'n', items()));
+    }
+}
@@ -2,11 +2,12 @@ use std::fmt::Display;
 
 use bounded_vec_deque::BoundedVecDeque;
 
-use super::{DisplayLine, ListState};
+use super::{DisplayLine, LineKind, ListState};
 
 /// A small state machine to track the display of selected items.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 enum SelectionState {
+    #[default]
     NotSeen,
     Started(usize),
     Complete,
@@ -23,12 +24,6 @@ impl SelectionState {
     }
 }
 
-impl Default for SelectionState {
-    fn default() -> Self {
-        SelectionState::NotSeen
-    }
-}
-
 /// Tracking for the display window used in selection scroll. The display window is the slice of
 /// lines that should be rendered to that screen. `top` is the first element of that slice.
 ///
@@ -158,7 +153,7 @@ pub(super) fn fixed<'a, I>(
     items: I,
     at: usize,
     window_size: usize,
-    _list_state: &mut ListState,
+    list_state: &mut ListState,
 ) -> <BoundedVecDeque<I::Item> as IntoIterator>::IntoIter
 where
     I: IntoIterator<Item = DisplayLine<'a>>,
@@ -167,15 +162,17 @@ where
     // the window actually shows the selection?
 
     let mut sel_state = SelectionState::default();
+    let mut seen = 0;
 
     // Create a queue of blank lines. This is sized to the fixed position,
     // if the iterator encounters a scenario when the selection starts with
     // (e.g.) the first display line, the selection will still be drawn in the
     // correct place.
     let mut buffer =
-        BoundedVecDeque::from_iter(std::iter::repeat(DisplayLine::filler("")).take(at), at);
+        BoundedVecDeque::from_iter(std::iter::repeat_n(DisplayLine::filler(""), at), at);
 
     for (i, dl) in items.into_iter().enumerate() {
+        seen = i + 1;
         sel_state.toggle(dl.must_display, i);
         match sel_state {
             // haven't seen the first display line in the selection.
@@ -194,9 +191,168 @@ where
             }
         }
     }
+
+    // Every line seen after the first line in the window is in the buffer, so the position of
+    // the window is found by discounting them and the filler.
+    let displayed = buffer.iter().filter(|l| l.kind != LineKind::Filler).count();
+    list_state.set_pos(seen - displayed);
+    buffer.into_iter()
+}
+
+/// Line selector for a window pinned in place by
+/// [`ListState::scroll_to`](super::ListState::scroll_to). The window starts at the current
+/// position, moving back only to stay filled at the end of the list.
+pub(super) fn pinned<'a, I>(
+    items: I,
+    window_size: usize,
+    list_state: &mut ListState,
+) -> <BoundedVecDeque<I::Item> as IntoIterator>::IntoIter
+where
+    I: IntoIterator<Item = DisplayLine<'a>>,
+{
+    let top = list_state.window_first;
+    let mut buffer = BoundedVecDeque::new(window_size);
+    let mut seen = 0;
+    for (i, l) in items.into_iter().enumerate() {
+        seen = i + 1;
+        buffer.push_back(l);
+        if seen >= top + window_size {
+            break;
+        }
+    }
+    list_state.set_pos(seen - buffer.len());
     buffer.into_iter()
 }
 
+/// The lines around the selection, collected so the window can be placed knowing where the
+/// selection starts and (if it fits in the window) ends.
+struct AroundSelection<'a> {
+    lines: Vec<DisplayLine<'a>>,
+    /// The position of the first of `lines` among all the lines
+    first: usize,
+    /// The positions of the first and last lines of the selection. The end is `None` if the
+    /// selection doesn't fit in the window.
+    selection: Option<(usize, Option<usize>)>,
+    /// Set if `lines` runs to the end of the list
+    ended: bool,
+}
+
+impl<'a> AroundSelection<'a> {
+    /// Collect up to `window_size` lines before the selection, and `window_size` lines from the
+    /// start of the selection. No window that shows the start of the selection needs any others.
+    fn collect<I>(items: I, window_size: usize) -> Self
+    where
+        I: IntoIterator<Item = DisplayLine<'a>>,
+    {
+        let mut before = BoundedVecDeque::new(window_size);
+        let mut after = Vec::new();
+        let mut sel_state = SelectionState::default();
+        let mut sel_end = None;
+        let mut seen = 0;
+        let mut ended = true;
+
+        for (i, l) in items.into_iter().enumerate() {
+            seen = i + 1;
+            sel_state.toggle(l.must_display, i);
+            match sel_state {
+                SelectionState::NotSeen => {
+                    before.push_back(l);
+                }
+                SelectionState::Started(_) => after.push(l),
+                SelectionState::Complete => {
+                    sel_end.get_or_insert(i - 1);
+                    after.push(l);
+                }
+            }
+            if after.len() >= window_size {
+                ended = false;
+                break;
+            }
+        }
+
+        // A selection that runs to the end of the list fits in the window, otherwise the
+        // collection would have stopped first.
+        let selection = (!after.is_empty()).then(|| {
+            let end = sel_end.or_else(|| ended.then(|| seen - 1));
+            (seen - after.len(), end)
+        });
+        let first = seen - after.len() - before.len();
+        let lines = before.into_iter().chain(after).collect();
+        Self {
+            lines,
+            first,
+            selection,
+            ended,
+        }
+    }
+
+    /// Take the window starting at the line `top`, after keeping it within the list.
+    fn window(
+        self,
+        top: usize,
+        window_size: usize,
+        list_state: &mut ListState,
+    ) -> Vec<DisplayLine<'a>> {
+        let last = self.first + self.lines.len();
+        let top = match self.ended {
+            true => top.min(last.saturating_sub(window_size)),
+            false => top,
+        };
+        let top = top.max(self.first);
+        list_state.set_pos(top);
+        self.lines
+            .into_iter()
+            .skip(top - self.first)
+            .take(window_size)
+            .collect()
+    }
+}
+
+/// Line selector for [`WindowType::ScrollOff`](super::WindowType::ScrollOff).
+pub(super) fn scroll_off<'a, I>(
+    items: I,
+    margin: usize,
+    window_size: usize,
+    list_state: &mut ListState,
+) -> std::vec::IntoIter<DisplayLine<'a>>
+where
+    I: IntoIterator<Item = DisplayLine<'a>>,
+{
+    let goal = list_state.window_first;
+    let around = AroundSelection::collect(items, window_size);
+    let top = match around.selection {
+        // The margin shrinks so the selection and both margins fit in the window
+        Some((start, Some(end))) => {
+            let height = end - start + 1;
+            let margin = margin.min(window_size.saturating_sub(height) / 2);
+            let lowest = (end + margin + 1).saturating_sub(window_size);
+            let highest = start.saturating_sub(margin);
+            goal.clamp(lowest, highest.max(lowest))
+        }
+        Some((start, None)) => start,
+        None => goal,
+    };
+    around.window(top, window_size, list_state).into_iter()
+}
+
+/// Line selector for [`WindowType::Centered`](super::WindowType::Centered).
+pub(super) fn centered<'a, I>(
+    items: I,
+    window_size: usize,
+    list_state: &mut ListState,
+) -> std::vec::IntoIter<DisplayLine<'a>>
+where
+    I: IntoIterator<Item = DisplayLine<'a>>,
+{
+    let around = AroundSelection::collect(items, window_size);
+    let top = match around.selection {
+        Some((start, Some(end))) => (start + end + 1).saturating_sub(window_size) / 2,
+        Some((start, None)) => start,
+        None => list_state.window_first,
+    };
+    around.window(top, window_size, list_state).into_iter()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -234,8 +390,14 @@ mod test {
                 style: Style::default(),
                 line: Spans::from(s),
                 must_display,
+                kind: LineKind::Item {
+                    index: i,
+                    line: 0,
+                    last: true,
+                },
                 left_indicator: " ".into(),
                 right_indicator: " ".into(),
+                header: false,
             }
         })
     }
@@ -341,4 +503,69 @@ mod test {
         assert!(res[1].must_display);
         assert!(res[2].must_display);
     }
+
+    #[test]
+    fn fixed_records_position() {
+        // a b c d E f g h i j
+        // result: c d E f
+        let mut state = ListState::new(10);
+        let res: Vec<DisplayLine> = fixed(make_list(4, 4), 2, 4, &mut state).collect();
+        assert_eq!(res[0].line.0[0].content, "c");
+        assert_eq!(state.window_first, 2);
+
+        // selection is closer to the start than the fixed position
+        let res: Vec<DisplayLine> = fixed(make_list(0, 0), 2, 4, &mut state).collect();
+        assert_eq!(res[2].line.0[0].content, "a");
+        assert_eq!(state.window_first, 0);
+    }
+
+    fn contents(lines: &[DisplayLine]) -> String {
+        lines.iter().map(|l| l.line.0[0].content.as_ref()).collect()
+    }
+
+    #[test]
+    fn scroll_off_keeps_margins() {
+        let mut state = ListState::new(10);
+        let res: Vec<DisplayLine> = scroll_off(make_list(4, 4), 1, 5, &mut state).collect();
+        assert_eq!(contents(&res), "bcdef");
+        assert_eq!(state.window_first, 1);
+
+        // moving up keeps a line above the selection
+        state.set_pos(5);
+        let res: Vec<DisplayLine> = scroll_off(make_list(4, 4), 1, 5, &mut state).collect();
+        assert_eq!(contents(&res), "defgh");
+
+        // the margin shrinks to fit the window
+        let res: Vec<DisplayLine> = scroll_off(make_list(2, 2), 5, 3, &mut state).collect();
+        assert_eq!(contents(&res), "bcd");
+
+        // and at the end of the list
+        state.set_pos(0);
+        let res: Vec<DisplayLine> = scroll_off(make_list(9, 9), 2, 5, &mut state).collect();
+        assert_eq!(contents(&res), "fghij");
+
+        // selections taller than the window show their start
+        let res: Vec<DisplayLine> = scroll_off(make_list(3, 8), 1, 3, &mut state).collect();
+        assert_eq!(contents(&res), "def");
+    }
+
+    #[test]
+    fn centers_selection() {
+        let mut state = ListState::new(10);
+        let res: Vec<DisplayLine> = centered(make_list(5, 5), 5, &mut state).collect();
+        assert_eq!(contents(&res), "defgh");
+        assert_eq!(state.window_first, 3);
+
+        let res: Vec<DisplayLine> = centered(make_list(1, 1), 5, &mut state).collect();
+        assert_eq!(contents(&res), "abcde");
+
+        let res: Vec<DisplayLine> = centered(make_list(9, 9), 5, &mut state).collect();
+        assert_eq!(contents(&res), "fghij");
+
+        let res: Vec<DisplayLine> = centered(make_list(4, 5), 4, &mut state).collect();
+        assert_eq!(contents(&res), "defg");
+
+        let res: Vec<DisplayLine> = centered(make_list(2, 7), 4, &mut state).collect();
+        assert_eq!(contents(&res), "cdef");
+    }
 }
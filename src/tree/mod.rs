@@ -0,0 +1,293 @@
+//! A tree widget with collapsible nodes.
+//!
+//! The tree is drawn by a [`StyledList`], with one [`ListItem`] per visible node. Any of the list
+//! options (styles, indicators, window type, scrollbar, ...) can be used by configuring the list
+//! with [`Tree::list`].
+use std::collections::BTreeSet;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Span, Spans, Text},
+    widgets::{StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::styled_list::{ListItem, StyledList};
+
+mod tree_item;
+mod tree_state;
+
+pub use tree_item::TreeItem;
+pub use tree_state::TreeState;
+
+use tree_state::{visible_nodes, Node};
+
+/// A tree of [`TreeItem`]s. Nodes with children can be expanded and collapsed using the
+/// [`TreeState`].
+pub struct Tree<'a, Id> {
+    items: Vec<TreeItem<'a, Id>>,
+    list: StyledList<'a, Vec<ListItem<'a>>>,
+    guide_style: Style,
+    expanded_symbol: &'static str,
+    collapsed_symbol: &'static str,
+    leaf_symbol: &'static str,
+}
+
+impl<'a, Id> Tree<'a, Id>
+where
+    Id: Clone + Ord,
+{
+    pub fn new(items: Vec<TreeItem<'a, Id>>) -> Self {
+        Self {
+            items,
+            list: StyledList::new(Vec::new()),
+            guide_style: Style::default(),
+            expanded_symbol: "▾ ",
+            collapsed_symbol: "▸ ",
+            leaf_symbol: "  ",
+        }
+    }
+
+    /// The list used to draw the tree. The items of `list` are replaced by the visible nodes of
+    /// the tree.
+    pub fn list(mut self, list: StyledList<'a, Vec<ListItem<'a>>>) -> Self {
+        self.list = list;
+        self
+    }
+
+    /// The style of the indentation guides.
+    pub fn guide_style(mut self, style: Style) -> Self {
+        self.guide_style = style;
+        self
+    }
+
+    /// The symbols drawn before expanded nodes, collapsed nodes, and nodes without children.
+    pub fn symbols(
+        mut self,
+        expanded: &'static str,
+        collapsed: &'static str,
+        leaf: &'static str,
+    ) -> Self {
+        self.expanded_symbol = expanded;
+        self.collapsed_symbol = collapsed;
+        self.leaf_symbol = leaf;
+        self
+    }
+
+    /// Walk the tree, recording every node and building the lines for the visible ones.
+    fn flatten(
+        &self,
+        flat: &mut Flattened<'_, 'a, Id>,
+        items: Vec<TreeItem<'a, Id>>,
+        parent: Option<usize>,
+        shown: bool,
+    ) {
+        let count = items.len();
+        for (n, item) in items.into_iter().enumerate() {
+            let last = n + 1 == count;
+            let has_children = !item.children.is_empty();
+            let is_expanded = has_children && flat.expanded.contains(&item.id);
+            let index = flat.nodes.len();
+            flat.nodes.push(Node {
+                id: item.id.clone(),
+                parent,
+                has_children,
+            });
+
+            // Top level nodes aren't connected to anything, so only children have guides.
+            let (connector, continued) = match (parent, last) {
+                (None, _) => ("", ""),
+                (Some(_), false) => ("├─", "│ "),
+                (Some(_), true) => ("└─", "  "),
+            };
+
+            if shown {
+                let symbol = if !has_children {
+                    self.leaf_symbol
+                } else if is_expanded {
+                    self.expanded_symbol
+                } else {
+                    self.collapsed_symbol
+                };
+                let guides = &flat.guides;
+                let first = format!("{guides}{connector}");
+                let rest = format!("{guides}{continued}{}", " ".repeat(symbol.width()));
+                let content = item.content.lines.into_iter().enumerate().map(|(i, line)| {
+                    let prefix = if i == 0 {
+                        vec![
+                            Span::styled(first.clone(), self.guide_style),
+                            Span::raw(symbol),
+                        ]
+                    } else {
+                        vec![Span::styled(rest.clone(), self.guide_style)]
+                    };
+                    Spans::from(prefix.into_iter().chain(line.0).collect::<Vec<_>>())
+                });
+                flat.lines
+                    .push(ListItem::new(Text::from(content.collect::<Vec<_>>())).style(item.style));
+            }
+
+            let len = flat.guides.len();
+            flat.guides.push_str(continued);
+            self.flatten(flat, item.children, Some(index), shown && is_expanded);
+            flat.guides.truncate(len);
+        }
+    }
+}
+
+/// The tree as it is walked by [`Tree::flatten`].
+struct Flattened<'s, 'a, Id> {
+    expanded: &'s BTreeSet<Id>,
+    /// The indentation guides for the ancestors of the nodes being walked
+    guides: String,
+    nodes: Vec<Node<Id>>,
+    lines: Vec<ListItem<'a>>,
+}
+
+impl<'a, Id> StatefulWidget for Tree<'a, Id>
+where
+    Id: Clone + Ord,
+{
+    type State = TreeState<Id>;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let items = std::mem::take(&mut self.items);
+        let mut flat = Flattened {
+            expanded: &state.expanded,
+            guides: String::new(),
+            nodes: Vec::new(),
+            lines: Vec::new(),
+        };
+        self.flatten(&mut flat, items, None, true);
+        let Flattened { nodes, lines, .. } = flat;
+        state.nodes = nodes;
+
+        // Keep the selection on the same node, wherever it is now displayed.
+        let visible = state.visible();
+        state.list.resize(lines.len());
+        if let Some(sel) = state.selected_visible(&visible) {
+            state.selected = Some(state.nodes[sel].id.clone());
+            let pos = visible_nodes(&visible).position(|n| n == sel).unwrap_or(0);
+            state.list.select(pos);
+        }
+
+        StatefulWidget::render(self.list.replace_items(lines), area, buf, &mut state.list);
+    }
+}
+
+impl<'a, Id> Widget for Tree<'a, Id>
+where
+    Id: Clone + Ord,
+{
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = TreeState::default();
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn items() -> Vec<TreeItem<'static, &'static str>> {
+        vec![
+            TreeItem::new("a", "a")
+                .child(TreeItem::new("a1", "a1").child(TreeItem::new("a1x", "a1x")))
+                .child(TreeItem::new("a2", "a2")),
+            TreeItem::new("b", "b").child(TreeItem::new("b1", "b1")),
+        ]
+    }
+
+    fn render(state: &mut TreeState<&'static str>) -> Vec<String> {
+        let area = Rect::new(0, 0, 10, 6);
+        let mut buf = Buffer::empty(area);
+        StatefulWidget::render(Tree::new(items()), area, &mut buf, state);
+        (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf.get(x, y).symbol.as_str())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draws_guides() {
+        let mut state = TreeState::new();
+        state.set_expanded("a", true);
+        state.set_expanded("a1", true);
+        assert_eq!(
+            render(&mut state),
+            vec!["▾ a", "├─▾ a1", "│ └─  a1x", "└─  a2", "▸ b", ""]
+        );
+    }
+
+    #[test]
+    fn selection_follows_node() {
+        let mut state = TreeState::new();
+        state.select("b");
+        render(&mut state);
+        assert_eq!(state.list_state().selected(), Some(1));
+
+        state.set_expanded("a", true);
+        render(&mut state);
+        assert_eq!(state.selected(), Some(&"b"));
+        assert_eq!(state.list_state().selected(), Some(3));
+
+        // hidden nodes select their visible ancestor
+        state.select("a1x");
+        render(&mut state);
+        assert_eq!(state.selected(), Some(&"a1"));
+    }
+
+    #[test]
+    fn emptied_tree() {
+        let mut state = TreeState::new();
+        state.set_expanded("a", true);
+        render(&mut state);
+        assert_eq!(state.list_state().selected(), Some(0));
+
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buf = Buffer::empty(area);
+        let tree = Tree::new(Vec::new()).list(StyledList::new(Vec::new()).empty_text("empty"));
+        StatefulWidget::render(tree, area, &mut buf, &mut state);
+        assert_eq!(state.list_state().selected(), None);
+        assert_eq!(
+            buf,
+            Buffer::with_lines(vec!["          ", "  empty   ", "          "])
+        );
+    }
+
+    #[test]
+    fn keyboard_operations() {
+        let mut state = TreeState::new();
+        render(&mut state);
+        assert_eq!(state.selected(), Some(&"a"));
+
+        state.expand();
+        state.next();
+        assert_eq!(state.selected(), Some(&"a1"));
+        state.expand();
+        state.next();
+        assert_eq!(state.selected(), Some(&"a1x"));
+        state.collapse();
+        assert_eq!(state.selected(), Some(&"a1"));
+        state.collapse();
+        assert!(!state.is_expanded(&"a1"));
+        state.next();
+        state.next();
+        assert_eq!(state.selected(), Some(&"b"));
+        state.next();
+        assert_eq!(state.selected(), Some(&"b"));
+
+        state.expand_all();
+        assert!(state.is_expanded(&"a1") && state.is_expanded(&"b"));
+        state.select("a1x");
+        state.collapse_all();
+        assert_eq!(state.selected(), Some(&"a"));
+    }
+}
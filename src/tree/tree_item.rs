@@ -0,0 +1,50 @@
+use ratatui::{style::Style, text::Text};
+
+/// A node in a [`Tree`](super::Tree).
+///
+/// Each node has an `id`, which is used by the [`TreeState`](super::TreeState) to remember which
+/// nodes are expanded and which is selected. Ids should be unique within the tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeItem<'a, Id> {
+    pub(super) id: Id,
+    pub(super) content: Text<'a>,
+    pub(super) style: Style,
+    pub(super) children: Vec<TreeItem<'a, Id>>,
+}
+
+impl<'a, Id> TreeItem<'a, Id> {
+    pub fn new<T>(id: Id, content: T) -> Self
+    where
+        T: Into<Text<'a>>,
+    {
+        TreeItem {
+            id,
+            content: content.into(),
+            style: Style::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the style for this node. This is used the same way as
+    /// [`ListItem::style`](crate::styled_list::ListItem::style).
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the children of this node.
+    pub fn children(mut self, children: Vec<TreeItem<'a, Id>>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Add a child after any existing children of this node.
+    pub fn child(mut self, child: TreeItem<'a, Id>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+}
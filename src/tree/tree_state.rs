@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+
+#[cfg(feature = "serde")]
+use serde_derive::{Deserialize, Serialize};
+
+use crate::styled_list::{Hit, ListState};
+
+/// State for a [`Tree`](super::Tree)
+///
+/// This tracks which nodes are expanded and which node is selected, by their ids. Since the
+/// selection is an id rather than a position, it stays on the same node when nodes above it are
+/// expanded or collapsed. If the selected node is hidden inside a collapsed node, the nearest
+/// visible ancestor is selected instead.
+///
+/// The movement methods use the shape of the tree as of the last render.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Id: serde::Serialize",
+        deserialize = "Id: serde::Deserialize<'de> + Ord"
+    ))
+)]
+pub struct TreeState<Id> {
+    pub(super) expanded: BTreeSet<Id>,
+    pub(super) selected: Option<Id>,
+    pub(super) list: ListState,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) nodes: Vec<Node<Id>>,
+}
+
+/// A node of the tree as of the last render, in the order they are displayed.
+#[derive(Debug, Clone)]
+pub(super) struct Node<Id> {
+    pub(super) id: Id,
+    pub(super) parent: Option<usize>,
+    pub(super) has_children: bool,
+}
+
+impl<Id> Default for TreeState<Id> {
+    fn default() -> Self {
+        TreeState {
+            expanded: BTreeSet::new(),
+            selected: None,
+            list: ListState::default(),
+            nodes: Vec::new(),
+        }
+    }
+}
+
+impl<Id> TreeState<Id>
+where
+    Id: Clone + Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of the selected node.
+    pub fn selected(&self) -> Option<&Id> {
+        self.selected.as_ref()
+    }
+
+    /// Select the node with `id`.
+    pub fn select(&mut self, id: Id) {
+        self.selected = Some(id);
+    }
+
+    pub fn is_expanded(&self, id: &Id) -> bool {
+        self.expanded.contains(id)
+    }
+
+    /// Expand or collapse the node with `id`.
+    pub fn set_expanded(&mut self, id: Id, expanded: bool) {
+        if expanded {
+            self.expanded.insert(id);
+        } else {
+            self.expanded.remove(&id);
+        }
+    }
+
+    /// Select the next visible node. Does nothing at the last node.
+    pub fn next(&mut self) {
+        self.step(1);
+    }
+
+    /// Select the previous visible node. Does nothing at the first node.
+    pub fn prev(&mut self) {
+        self.step(-1);
+    }
+
+    /// Expand the selected node.
+    pub fn expand(&mut self) {
+        if let Some(i) = self.selected_node() {
+            if self.nodes[i].has_children {
+                self.expanded.insert(self.nodes[i].id.clone());
+            }
+        }
+    }
+
+    /// Collapse the selected node. If it is already collapsed (or has no children), select its
+    /// parent instead.
+    pub fn collapse(&mut self) {
+        if let Some(i) = self.selected_node() {
+            if !self.expanded.remove(&self.nodes[i].id) {
+                self.select_parent();
+            }
+        }
+    }
+
+    /// Expand the selected node if it is collapsed, otherwise collapse it.
+    pub fn toggle(&mut self) {
+        if let Some(i) = self.selected_node() {
+            let id = &self.nodes[i].id;
+            if !self.expanded.remove(id) && self.nodes[i].has_children {
+                self.expanded.insert(id.clone());
+            }
+        }
+    }
+
+    /// Select the parent of the selected node.
+    pub fn select_parent(&mut self) {
+        if let Some(parent) = self.selected_node().and_then(|i| self.nodes[i].parent) {
+            self.selected = Some(self.nodes[parent].id.clone());
+        }
+    }
+
+    /// Expand every node that has children.
+    pub fn expand_all(&mut self) {
+        self.expanded.extend(
+            self.nodes
+                .iter()
+                .filter(|n| n.has_children)
+                .map(|n| n.id.clone()),
+        );
+    }
+
+    /// Collapse every node. The selection moves to the top level node containing it.
+    pub fn collapse_all(&mut self) {
+        self.expanded.clear();
+        if let Some(mut i) = self.selected_node() {
+            while let Some(parent) = self.nodes[i].parent {
+                i = parent;
+            }
+            self.selected = Some(self.nodes[i].id.clone());
+        }
+    }
+
+    /// The id of the node drawn at a position in the terminal, as of the last render.
+    pub fn node_at(&self, x: u16, y: u16) -> Option<&Id> {
+        match self.list.item_at(x, y)? {
+            Hit::Item { index, .. } => {
+                let node = visible_nodes(&self.visible()).nth(index)?;
+                Some(&self.nodes[node].id)
+            }
+            Hit::Header { .. } | Hit::Separator { .. } => None,
+        }
+    }
+
+    /// The state of the list used to draw the tree, e.g. for the position of the window.
+    pub fn list_state(&self) -> &ListState {
+        &self.list
+    }
+
+    /// Which of the nodes are visible, i.e. not inside a collapsed node.
+    pub(super) fn visible(&self) -> Vec<bool> {
+        let mut visible = Vec::with_capacity(self.nodes.len());
+        for n in &self.nodes {
+            let shown = match n.parent {
+                Some(p) => visible[p] && self.expanded.contains(&self.nodes[p].id),
+                None => true,
+            };
+            visible.push(shown);
+        }
+        visible
+    }
+
+    /// The node to show as selected. This is the selected node if it is visible, or its nearest
+    /// visible ancestor. If nothing is selected (or the selected node is gone) the first node
+    /// is used.
+    pub(super) fn selected_visible(&self, visible: &[bool]) -> Option<usize> {
+        let found = self
+            .selected
+            .as_ref()
+            .and_then(|id| self.nodes.iter().position(|n| &n.id == id));
+        match found {
+            Some(mut i) => {
+                while !visible[i] {
+                    i = self.nodes[i].parent?;
+                }
+                Some(i)
+            }
+            None => visible_nodes(visible).next(),
+        }
+    }
+
+    fn selected_node(&self) -> Option<usize> {
+        self.selected_visible(&self.visible())
+    }
+
+    fn step(&mut self, by: isize) {
+        let visible = self.visible();
+        let Some(sel) = self.selected_visible(&visible) else {
+            return;
+        };
+        let nodes: Vec<usize> = visible_nodes(&visible).collect();
+        let pos = nodes.iter().position(|&n| n == sel).unwrap_or(0);
+        let pos = pos.saturating_add_signed(by).min(nodes.len() - 1);
+        self.selected = Some(self.nodes[nodes[pos]].id.clone());
+    }
+}
+
+/// The indices of the visible nodes.
+pub(super) fn visible_nodes(visible: &[bool]) -> impl Iterator<Item = usize> + '_ {
+    visible
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| v)
+        .map(|(i, _)| i)
+}